@@ -8,25 +8,41 @@ pub mod bv_error;
 
 pub mod generic_parser;
 
+pub mod ini_parser;
+
 pub mod validator;
 
+pub mod path_resolver;
+
+pub mod montage;
+
 use header::BVheader;
+use header::parser::parse_dataformat::DataFormat;
 use marker::BVMarker;
-use data::BVData;
+use marker::parser::MarkerType;
+use data::{BVData, sample::Sample, segments::Segment, native::BVNativeData, mapped::MappedBVData, complex::BVComplexData};
+use data::process::scale_channels::scale_window;
+use data::parser::read_datfile::{get_file_as_byte_vec, read_seekable_as_byte_vec};
 use bv_error::Error;
 
+use std::io::{Read, Seek};
+use std::path::Path;
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
+
+use path_resolver::{ResolveOptions, resolve_sibling_path};
 use validator::{IsValid, validate_num_chan, parse_invalid_to_error};
 
 /// Main struct of the file reader
 /// 
 /// Combines Header, Marker and Data
 /// 
-///  
+/// Samples are `f32` by default, see [BVFile::from_header_as] for other sample types.
 #[derive(Debug, PartialEq)]
-pub struct BVFile {
+pub struct BVFile<T: Sample = f32> {
     pub bv_header: BVheader,
     pub bv_marker: BVMarker,
-    pub bv_data: BVData,
+    pub bv_data: BVData<T>,
 }
 
 
@@ -34,18 +50,75 @@ impl BVFile {
     /// Main function for the file reader
     /// 
     /// Takes the header filename and returns the fully parsed struct or an error if anything went wrong
+    /// 
+    /// The samples are `f32`: files with `INT_32`, `UINT_32` or `IEEE_FLOAT_64` data return Error::LossyConversion
+    /// and have to be read as `f64` with [BVFile::from_header_as].
     pub fn from_header(headerfile: &str) -> Result<Self, Error> {
+        BVFile::from_header_as(headerfile)
+    }
+}
 
-        let root_path: Vec<&str> = headerfile.split("/").collect();
-        let root_path = &root_path[0..root_path.len()-1];
+impl BVFile {
+    /// Builds the file from the decoded header and marker text and any data source, nothing is read from disk
+    /// 
+    /// The data source is read from its current position to the end, the buffer is sized once from the remaining length.
+    /// Use [BVheader::from_bytes] / [BVMarker::from_bytes] first if the text is not decoded yet,
+    /// or [data::reader::BVDataReader] for windowed reads from the data source.
+    /// ```
+    /// use std::io::Cursor;
+    /// use crate::bvreader::bv_reader::BVFile;
+    /// 
+    /// let header = std::fs::read_to_string("src/bv_reader/data/testfiles/01_header.vhdr").unwrap();
+    /// let marker = std::fs::read_to_string("src/bv_reader/data/testfiles/01_marker.vmrk").unwrap();
+    /// let data = std::fs::read("src/bv_reader/data/testfiles/01_data.eeg").unwrap();
+    /// 
+    /// let metafile = BVFile::from_sources(&header, &marker, Cursor::new(data)).unwrap();
+    /// assert_eq!(metafile.bv_data.num_chan, 71);
+    /// ```
+    pub fn from_sources<R: Read + Seek>(header_text: &str, marker_text: &str, data: R) -> Result<Self, Error> {
+        BVFile::from_sources_as(header_text, marker_text, data)
+    }
+}
+
+impl<T: Sample> BVFile<T> {
+    /// Same as [BVFile::from_header] but converts the samples to `T`
+    /// 
+    /// Files with `INT_32`, `UINT_32` or `IEEE_FLOAT_64` data have to be read as `f64`:
+    /// ```
+    /// use crate::bvreader::bv_reader::BVFile;
+    /// 
+    /// let metafile = BVFile::<f64>::from_header_as("src/bv_reader/data/testfiles/01_header.vhdr").unwrap();
+    /// ```
+    pub fn from_header_as(headerfile: &str) -> Result<Self, Error> {
+        BVFile::from_header_with(headerfile, &ResolveOptions::default())
+    }
 
+    /// Same as [BVFile::from_header_as] with options for finding the data and marker file
+    /// 
+    /// ```
+    /// use crate::bvreader::bv_reader::{BVFile, path_resolver::ResolveOptions};
+    /// 
+    /// let options = ResolveOptions{case_insensitive: true};
+    /// let metafile = BVFile::<f32>::from_header_with("src/bv_reader/data/testfiles/01_header.vhdr", &options).unwrap();
+    /// ```
+    pub fn from_header_with(headerfile: &str, options: &ResolveOptions) -> Result<Self, Error> {
 
-        let bv_header = BVheader::from_file(headerfile)?;
-        let marker_path = format!("{}/{}",root_path.join("/"),&bv_header.marker_file);
-        let bv_marker = BVMarker::from_file(&marker_path)?;
+        let (bv_header, bv_marker, data_path) = read_header_and_marker(headerfile, options)?;
+        let mut bv_data = data_from_bytes(&bv_header, get_file_as_byte_vec(&data_path)?)?;
+        bv_data.data_path = data_path;
+
+        Ok(BVFile{
+            bv_header,
+            bv_marker,
+            bv_data,
+        })
+    }
 
-        let data_path = format!("{}/{}",root_path.join("/"),&bv_header.data_file);
-        let bv_data = BVData::from_file(&data_path, bv_header.num_channels, bv_header.binary_format ,bv_header.data_orientation, bv_header.use_big_endian)?;
+    /// Same as [BVFile::from_sources] but converts the samples to `T`
+    pub fn from_sources_as<R: Read + Seek>(header_text: &str, marker_text: &str, data: R) -> Result<Self, Error> {
+        let bv_header = BVheader::from_text(header_text)?;
+        let bv_marker = BVMarker::from_text(marker_text)?;
+        let bv_data = data_from_bytes(&bv_header, read_seekable_as_byte_vec(data)?)?;
 
         Ok(BVFile{
             bv_header,
@@ -54,6 +127,51 @@ impl BVFile {
         })
     }
 
+    /// Returns the wall-clock time of a 0-based sample index
+    ///
+    /// Counts from the date of the "New Segment" marker the sample belongs to, see [BVMarker::segment_for_sample].
+    /// Returns Option<None> if the marker file contains no dated segment.
+    pub fn sample_time(&self, sample_idx: usize) -> Option<SystemTime> {
+        let segment = self.bv_marker.segment_for_sample(sample_idx)?;
+        let start = segment.marker_date.as_ref()?.to_system_time()?;
+
+        // marker positions are 1-based
+        let offset = sample_idx as f64 + 1. - segment.marker_position as f64;
+        let offset_nanos = (offset * self.bv_header.sampling_interval * 1_000.).round();
+        if offset_nanos >= 0. {
+            start.checked_add(Duration::from_nanos(offset_nanos as u64))
+        } else {
+            start.checked_sub(Duration::from_nanos(-offset_nanos as u64))
+        }
+    }
+
+    /// Returns the segments of the data, each tied to the "New Segment" marker at its first sample
+    /// 
+    /// Segments are `SegmentDataPoints` long, data that is not segmented is returned as one segment.
+    /// Returns Error::MissingSegmentDataPoints for segmented data without `SegmentDataPoints`, see [header::parser::parse_segmentation::SegmentationInfo::is_segmented].
+    pub fn segments(&self) -> Result<Vec<Segment<'_, T>>, Error> {
+        let chan_len = self.bv_data.data.first().map_or(0, |channel| channel.len());
+        let segmentation = &self.bv_header.segmentation;
+        let segment_len = match segmentation.segment_data_points {
+            _ if !segmentation.is_segmented() => chan_len,
+            Some(segment_len) => segment_len,
+            None => return Err(Error::MissingSegmentDataPoints),
+        };
+        if chan_len == 0 {return Ok(vec![])}
+
+        Ok(self.bv_data.segments(segment_len)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, channels)| Segment{
+                index,
+                // marker positions are 1-based
+                marker: self.bv_marker.marker_data.iter()
+                    .find(|marker| marker.marker_type == MarkerType::NewSegment && marker.marker_position == index * segment_len + 1),
+                channels,
+            })
+            .collect())
+    }
+
     /// Sequential validation of file parameters
     /// 
     /// Includes:
@@ -62,7 +180,7 @@ impl BVFile {
     /// 
     pub fn validate(&self) -> Result<(), Error> {
 
-        let valid = validate_num_chan(&self);
+        let valid = validate_num_chan(self);
         if valid != IsValid::True {return Err(parse_invalid_to_error(&valid));}
         
         Ok(())
@@ -70,9 +188,156 @@ impl BVFile {
 
 }
 
+/// Decodes the data according to the data format of the header, `data_path` stays empty
+fn data_from_bytes<T: Sample>(bv_header: &BVheader, bytestring: Vec<u8>) -> Result<BVData<T>, Error> {
+    if bv_header.data_type.is_complex() {return Err(Error::ComplexDataType)}
+    if bv_header.num_channels == 0 {return Err(Error::NoChannels)}
+    match bv_header.data_format {
+        DataFormat::BINARY => BVData::from_bytes_as(bytestring, bv_header.num_channels, bv_header.binary_format ,bv_header.data_orientation, bv_header.use_big_endian),
+        DataFormat::ASCII => BVData::from_ascii_bytes_as(&bytestring, bv_header.num_channels, &bv_header.ascii_info.unwrap_or_default(), bv_header.data_orientation),
+        DataFormat::Unknown => Err(Error::InvalidDataFormat),
+    }
+}
+
+/// Reads header and marker file, returns both along with the path of the data file
+fn read_header_and_marker(headerfile: &str, options: &ResolveOptions) -> Result<(BVheader, BVMarker, String), Error> {
+
+    let bv_header = BVheader::from_file(headerfile)?;
+    let marker_path = resolve_sibling_path(Path::new(headerfile), &bv_header.marker_file, options)?;
+    let bv_marker = BVMarker::from_file(&marker_path.to_string_lossy())?;
+
+    let data_path = resolve_sibling_path(Path::new(headerfile), &bv_header.data_file, options)?;
+    Ok((bv_header, bv_marker, data_path.to_string_lossy().to_string()))
+}
+
+
+/// Same as [BVFile] but keeps the samples in their on-disk type
+/// 
+/// Only supports `DataFormat=BINARY`
+#[derive(Debug, PartialEq)]
+pub struct BVNativeFile {
+    pub bv_header: BVheader,
+    pub bv_marker: BVMarker,
+    pub bv_data: BVNativeData,
+}
+
+impl BVNativeFile {
+    pub fn from_header(headerfile: &str) -> Result<Self, Error> {
+        BVNativeFile::from_header_with(headerfile, &ResolveOptions::default())
+    }
+
+    /// Same as [BVNativeFile::from_header] with options for finding the data and marker file
+    pub fn from_header_with(headerfile: &str, options: &ResolveOptions) -> Result<Self, Error> {
+
+        let (bv_header, bv_marker, data_path) = read_header_and_marker(headerfile, options)?;
+        if bv_header.data_format != DataFormat::BINARY {return Err(Error::InvalidDataFormat)}
+        if bv_header.data_type.is_complex() {return Err(Error::ComplexDataType)}
+        let bv_data = BVNativeData::from_file(&data_path, bv_header.num_channels, bv_header.binary_format ,bv_header.data_orientation, bv_header.use_big_endian)?;
+
+        Ok(BVNativeFile{
+            bv_header,
+            bv_marker,
+            bv_data,
+        })
+    }
+
+    /// Converts the samples to physical units according to the resolution of each channel
+    pub fn to_physical<T: Sample>(&self) -> Result<BVFile<T>, Error> {
+        let data = self.bv_data.to_physical(&self.bv_header.channel_info)?;
+        Ok(BVFile{
+            bv_header: self.bv_header.clone(),
+            bv_marker: self.bv_marker.clone(),
+            bv_data: BVData{
+                data_path: self.bv_data.data_path.clone(),
+                num_chan: data.len(),
+                data,
+            },
+        })
+    }
+}
+
+/// Same as [BVFile] but memory-maps the data file instead of reading it
+/// 
+/// Samples are decoded per window, see [BVMappedFile::read_window]. Only supports `DataFormat=BINARY`.
+#[derive(Debug)]
+pub struct BVMappedFile {
+    pub bv_header: BVheader,
+    pub bv_marker: BVMarker,
+    pub bv_data: MappedBVData,
+}
+
+impl BVMappedFile {
+    pub fn from_header(headerfile: &str) -> Result<Self, Error> {
+        BVMappedFile::from_header_with(headerfile, &ResolveOptions::default())
+    }
+
+    /// Same as [BVMappedFile::from_header] with options for finding the data and marker file
+    pub fn from_header_with(headerfile: &str, options: &ResolveOptions) -> Result<Self, Error> {
+
+        let (bv_header, bv_marker, data_path) = read_header_and_marker(headerfile, options)?;
+        if bv_header.data_format != DataFormat::BINARY {return Err(Error::InvalidDataFormat)}
+        if bv_header.data_type.is_complex() {return Err(Error::ComplexDataType)}
+        let bv_data = MappedBVData::from_file(&data_path, bv_header.num_channels, bv_header.binary_format ,bv_header.data_orientation, bv_header.use_big_endian)?;
+
+        Ok(BVMappedFile{
+            bv_header,
+            bv_marker,
+            bv_data,
+        })
+    }
+
+    /// Decodes the selected channels in the sample range and scales them according to their resolution
+    pub fn read_window<T: Sample>(&self, channels: &[usize], samples: Range<usize>) -> Result<Vec<Vec<T>>, Error> {
+        let mut window = self.bv_data.read_window::<T>(channels, samples)?;
+        scale_window(&mut window, channels, &self.bv_header.channel_info)?;
+        Ok(window)
+    }
+}
+
+/// Same as [BVFile] for complex data types, e.g. `DataType=FREQUENCYDOMAIN_COMPLEX`
+/// 
+/// Only supports `DataFormat=BINARY`
+#[derive(Debug, PartialEq)]
+pub struct BVComplexFile<T: Sample = f32> {
+    pub bv_header: BVheader,
+    pub bv_marker: BVMarker,
+    pub bv_data: BVComplexData<T>,
+}
+
+impl BVComplexFile {
+    pub fn from_header(headerfile: &str) -> Result<Self, Error> {
+        BVComplexFile::from_header_as(headerfile)
+    }
+}
+
+impl<T: Sample> BVComplexFile<T> {
+    /// Same as [BVComplexFile::from_header] but converts the values to `T`
+    pub fn from_header_as(headerfile: &str) -> Result<Self, Error> {
+        BVComplexFile::from_header_with(headerfile, &ResolveOptions::default())
+    }
+
+    /// Same as [BVComplexFile::from_header_as] with options for finding the data and marker file
+    pub fn from_header_with(headerfile: &str, options: &ResolveOptions) -> Result<Self, Error> {
+
+        let (bv_header, bv_marker, data_path) = read_header_and_marker(headerfile, options)?;
+        if bv_header.data_format != DataFormat::BINARY {return Err(Error::InvalidDataFormat)}
+        if !bv_header.data_type.is_complex() {return Err(Error::RealDataType)}
+        let bv_data = BVComplexData::from_file_as(&data_path, bv_header.num_channels, bv_header.binary_format ,bv_header.data_orientation, bv_header.use_big_endian)?;
+
+        Ok(BVComplexFile{
+            bv_header,
+            bv_marker,
+            bv_data,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use header::parser::parse_header_version::HeaderVersion;
+    use data::native::NativeSamples;
+    use header::parser::parse_coordinates::SphericalPosition;
 
     #[test]
     fn test_parse_bv_file() {
@@ -83,11 +348,246 @@ mod tests {
         assert_eq!(output.bv_header.num_channels, expected);
     }
 
+    #[test]
+    fn test_parse_bv_file_version_2() {
+        let input = "src/bv_reader/data/testfiles/02_header.vhdr";
+        let output = BVFile::from_header(input).unwrap();
+        assert_eq!(output.bv_header.header_version, HeaderVersion::V2_0);
+        assert_eq!(output.bv_marker.header_version, HeaderVersion::V2_0);
+        assert_eq!(output.bv_header.num_channels, 71);
+        assert_eq!(output.bv_header.channel_info.len(), 71);
+        assert_eq!(output.bv_header.channel_user_infos[1].owner_id, "Ch64");
+        assert_eq!(output.bv_header.channel_user_infos[2].values, vec!["-5", "5"]);
+        assert_eq!(output.bv_header.channel_info[63].extra_fields, vec!["AUX", "1"]);
+        assert_eq!(output.bv_header.channel_info[0].position, Some(SphericalPosition{radius: 1., theta: -90., phi: -72.}));
+        assert_eq!(output.bv_header.channel_info[63].position, Option::None);
+        assert_eq!(output.bv_marker.marker_user_infos[0].name, "Trial");
+    }
+
+    #[test]
+    fn test_sample_time() {
+        let input = "src/bv_reader/data/testfiles/01_header.vhdr";
+        let output = BVFile::from_header(input).unwrap();
+        // New Segment at position 1 dated 2020-03-16T12:58:05.099157Z, sampling interval 2000 µs
+        let start = std::time::UNIX_EPOCH + Duration::new(1_584_363_485, 99_157_000);
+        assert_eq!(output.sample_time(0), Some(start));
+        assert_eq!(output.sample_time(10), Some(start + Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_parse_bv_file_int32() {
+        let input = "src/bv_reader/data/testfiles/04_header_int32.vhdr";
+        let output = BVFile::from_header(input);
+        let expected = Err(Error::LossyConversion("INT_32".to_string(), "f32".to_string()));
+        assert_eq!(output.map(|_| ()), expected);
+
+        let mut output = BVFile::<f64>::from_header_as(input).unwrap();
+        assert_eq!(output.bv_data.data, vec![vec![16777217., 2147483647., 0.], vec![-1., -2147483648., 3.]]);
+        output.bv_data.scale_channels(&output.bv_header.channel_info).unwrap();
+        assert_eq!(output.bv_data.data[0][0], 8388608.5);
+    }
+
+    #[test]
+    fn test_parse_bv_file_ascii() {
+        let input = "src/bv_reader/data/testfiles/05_header_ascii.vhdr";
+        let output = BVFile::from_header(input).unwrap();
+        assert_eq!(output.bv_header.data_format, DataFormat::ASCII);
+        assert_eq!(output.bv_data.num_chan, 2);
+        assert_eq!(output.bv_data.data, vec![vec![1.5, -2., 3.], vec![4., 5.25, 6.]]);
+    }
+
+    #[test]
+    fn test_parse_bv_file_without_dataformat() {
+        let header = "Brain Vision Data Exchange Header File Version 1.0
+        [Common Infos]
+        DataFile=data.eeg
+        MarkerFile=data.vmrk
+        DataOrientation=MULTIPLEXED
+        NumberOfChannels=2
+        SamplingInterval=2000
+        [Binary Infos]
+        BinaryFormat=INT_16";
+        let marker = "Brain Vision Data Exchange Marker File, Version 1.0";
+        let data: Vec<u8> = [1i16, 2, 3, 4].iter().flat_map(|x| x.to_le_bytes()).collect();
+        let output = BVFile::from_sources(header, marker, std::io::Cursor::new(data)).unwrap();
+        assert_eq!(output.bv_header.data_format, DataFormat::BINARY);
+        assert_eq!(output.bv_data.data, vec![vec![1., 3.], vec![2., 4.]]);
+
+        let header = header.replace("NumberOfChannels", "DataFormat=BINARY_COMPRESSED\n        NumberOfChannels");
+        let output = BVFile::from_sources(&header, marker, std::io::Cursor::new(vec![0u8; 4]));
+        assert_eq!(output.map(|_| ()), Err(Error::InvalidDataFormat));
+    }
+
+    #[test]
+    fn test_parse_bv_complex_file() {
+        let input = "src/bv_reader/data/testfiles/06_header_complex.vhdr";
+        let mut output = BVComplexFile::from_header(input).unwrap();
+        assert_eq!(output.bv_data.magnitude(0), Some(vec![5., 2.]));
+        assert_eq!(output.bv_data.real(1).unwrap().collect::<Vec<f32>>(), vec![1., 6.]);
+        assert_eq!(output.bv_header.frequency_bins(), vec![0., 0.5]);
+        output.bv_data.scale_channels(&output.bv_header.channel_info).unwrap();
+        assert_eq!(output.bv_data.magnitude(1), Some(vec![2., 20.]));
+
+        let output = BVFile::from_header(input);
+        let expected = Err(Error::ComplexDataType);
+        assert_eq!(output.map(|_| ()), expected);
+
+        let output = BVComplexFile::from_header("src/bv_reader/data/testfiles/01_header.vhdr");
+        let expected = Err(Error::RealDataType);
+        assert_eq!(output.map(|_| ()), expected);
+    }
+
+    #[test]
+    fn test_segments() {
+        let header = "Brain Vision Data Exchange Header File Version 1.0
+        [Common Infos]
+        DataFile=erp.dat
+        MarkerFile=erp.vmrk
+        DataFormat=BINARY
+        DataOrientation=VECTORIZED
+        NumberOfChannels=2
+        SamplingInterval=2000
+        SegmentationType=MARKERBASED
+        SegmentDataPoints=2
+        Averaged=YES
+        AveragedSegments=10
+        [Binary Infos]
+        BinaryFormat=INT_16
+        [Channel Infos]
+        Ch1=Fz,,1,µV
+        Ch2=Cz,,1,µV";
+        let marker = "Brain Vision Data Exchange Marker File, Version 1.0
+        [Marker Infos]
+        Mk1=New Segment,,1,1,0,20200316125805099157
+        Mk2=Stimulus,S  1,2,1,0
+        Mk3=New Segment,,3,1,0,20200316125806099157";
+        let data: Vec<u8> = [1i16, 2, 3, 4, 5, 6, 7, 8].iter().flat_map(|x| x.to_le_bytes()).collect();
+
+        let output = BVFile::from_sources(header, marker, std::io::Cursor::new(data.clone())).unwrap();
+        assert!(output.bv_header.segmentation.averaged);
+        assert_eq!(output.bv_header.segmentation.averaged_segments, Some(10));
+
+        let segments = output.segments().unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].index, 1);
+        assert_eq!(segments[1].num_samples(), 2);
+        assert_eq!(segments[1].marker.unwrap().marker_id, "Mk3");
+        assert_eq!(segments[1].channels, vec![&[3f32, 4.][..], &[7., 8.][..]]);
+
+        // unknown segmentation types are split like the known ones
+        let unknown = header.replace("MARKERBASED", "AUTOMATIC");
+        let output = BVFile::from_sources(&unknown, marker, std::io::Cursor::new(data.clone())).unwrap();
+        assert_eq!(output.segments().unwrap().len(), 2);
+
+        for segmentation_type in ["MARKERBASED", "FIXTIME", "MANUAL", "AUTOMATIC"] {
+            let missing = header.replace("MARKERBASED", segmentation_type).replace("SegmentDataPoints=2", "");
+            let output = BVFile::from_sources(&missing, marker, std::io::Cursor::new(data.clone())).unwrap();
+            assert_eq!(output.segments().map(|segments| segments.len()), Err(Error::MissingSegmentDataPoints));
+        }
+    }
+
+    #[test]
+    fn test_segments_not_segmented() {
+        let input = "src/bv_reader/data/testfiles/01_header.vhdr";
+        let output = BVFile::from_header(input).unwrap();
+        let segments = output.segments().unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].num_samples(), 100);
+        assert_eq!(segments[0].marker.unwrap().marker_id, "Mk1");
+    }
+
+    #[test]
+    fn test_parse_bv_native_file() {
+        let input = "src/bv_reader/data/testfiles/04_header_int32.vhdr";
+        let output = BVNativeFile::from_header(input).unwrap();
+        assert_eq!(output.bv_data.samples, NativeSamples::I32(vec![vec![16777217, 2147483647, 0], vec![-1, -2147483648, 3]]));
+
+        let output = output.to_physical::<f64>().unwrap();
+        assert_eq!(output.bv_data.data[0], vec![8388608.5, 1073741823.5, 0.]);
+    }
+
+    #[test]
+    fn test_parse_bv_native_file_ascii() {
+        let input = "src/bv_reader/data/testfiles/05_header_ascii.vhdr";
+        let output = BVNativeFile::from_header(input);
+        let expected = Err(Error::InvalidDataFormat);
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_bv_mapped_file() {
+        let input = "src/bv_reader/data/testfiles/01_header.vhdr";
+        let mapped = BVMappedFile::from_header(input).unwrap();
+        let mut full = BVFile::from_header(input).unwrap();
+        full.bv_data.scale_channels(&full.bv_header.channel_info).unwrap();
+
+        let output = mapped.read_window::<f32>(&[0, 64], 90..100).unwrap();
+        assert_eq!(output, vec![full.bv_data.data[0][90..100].to_vec(), full.bv_data.data[64][90..100].to_vec()]);
+        assert_eq!(mapped.read_window::<f32>(&[], 0..10), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_parse_files_with_resolve_options() {
+        // headers in a temporary directory, pointing to the test files with upper case names
+        let testfiles = std::fs::canonicalize("src/bv_reader/data/testfiles").unwrap();
+        let dir = std::env::temp_dir().join(format!("bvreader_resolve_options_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write_header = |name: &str| {
+            let header = std::fs::read_to_string(testfiles.join(name)).unwrap()
+                .replace("DataFile=", &format!("DataFile={}/", testfiles.display()))
+                .replace("MarkerFile=", &format!("MarkerFile={}/", testfiles.display()))
+                .replace("_data_int32.eeg", "_DATA_INT32.EEG")
+                .replace("_data_complex.dat", "_DATA_COMPLEX.DAT");
+            std::fs::write(dir.join(name), header).unwrap();
+            dir.join(name).to_string_lossy().to_string()
+        };
+        let options = ResolveOptions{case_insensitive: true};
+
+        let input = write_header("04_header_int32.vhdr");
+        let output = BVNativeFile::from_header_with(&input, &options).unwrap();
+        assert_eq!(output.bv_data.samples, NativeSamples::I32(vec![vec![16777217, 2147483647, 0], vec![-1, -2147483648, 3]]));
+        let output = BVMappedFile::from_header_with(&input, &options).unwrap();
+        assert_eq!(output.read_window::<f64>(&[1], 0..2), Ok(vec![vec![-0.5, -1073741824.]]));
+
+        let input = write_header("06_header_complex.vhdr");
+        let output = BVComplexFile::<f32>::from_header_with(&input, &options).unwrap();
+        assert_eq!(output.bv_data.magnitude(0), Some(vec![5., 2.]));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_bv_file_from_sources() {
+        let data = std::fs::read("src/bv_reader/data/testfiles/01_data.eeg").unwrap();
+        let output = BVFile::from_sources(
+            &std::fs::read_to_string("src/bv_reader/data/testfiles/01_header.vhdr").unwrap(),
+            &std::fs::read_to_string("src/bv_reader/data/testfiles/01_marker.vmrk").unwrap(),
+            std::io::Cursor::new(data),
+        ).unwrap();
+        assert_eq!(output.bv_header.header_path, "");
+        assert_eq!(output.bv_header.num_channels, 71);
+        assert_eq!(output.bv_data.num_chan, 71);
+        assert_eq!(output.bv_data.data[70].len(), 100);
+        assert_eq!(output.bv_data.data_path, "");
+        assert_eq!(output.bv_marker.marker_data.len(), 86);
+        assert_eq!(output.bv_marker.marker_data[0].marker_type, MarkerType::NewSegment);
+
+        // header without NumberOfChannels
+        let header = "Brain Vision Data Exchange Header File Version 1.0
+        [Common Infos]
+        DataFormat=BINARY
+        DataOrientation=MULTIPLEXED
+        [Binary Infos]
+        BinaryFormat=IEEE_FLOAT_32";
+        let output = BVFile::from_sources(header, "Brain Vision Data Exchange Marker File, Version 1.0", std::io::Cursor::new(vec![0; 8]));
+        assert_eq!(output.map(|_| ()), Err(Error::NoChannels));
+    }
+
     #[test]
     fn test_parse_bv_file_empty() {
         let input = "";
         let output = BVFile::from_header(input);
-        assert_eq!(output.is_err(), true);
+        assert!(output.is_err());
     }
 
     #[test]
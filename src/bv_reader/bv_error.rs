@@ -10,10 +10,22 @@ pub enum Error {
     FileMeta(String),
     #[error("Could not read file @ {0}")]
     FileRead(String),
+    #[error("Could not find file {0}, tried: {1:?}")]
+    FileNotFound(String, Vec<String>),
 
     // Header + Marker
     #[error("Could not parse header version")]
     InvalidHeaderVersion,
+    #[error("Unsupported header version {0}")]
+    UnsupportedHeaderVersion(String),
+    #[error("Invalid channel info entry: {0}")]
+    InvalidChannelInfo(String),
+    #[error("Invalid coordinates entry: {0}")]
+    InvalidCoordinates(String),
+    #[error("Invalid marker entry: {0}")]
+    InvalidMarker(String),
+    #[error("Invalid timecode {0}")]
+    InvalidTimecode(String),
 
     // Data 
     #[error("Invalid binary format")]
@@ -22,6 +34,12 @@ pub enum Error {
     EmptyBinary,
     #[error("Invalid data orientation")]
     InvalidDataOrientation,
+    #[error("Number of channels is missing or 0")]
+    NoChannels,
+    #[error("Invalid data format")]
+    InvalidDataFormat,
+    #[error("Binary format {0} can not be represented as {1} without loss of precision, use f64 instead")]
+    LossyConversion(String, String),
 
     // Binary parser
     #[error("Invalid binary dimensions for binary with format {0}. Parser stopped at byte {1} while binary contains {2} bytes")]
@@ -29,6 +47,36 @@ pub enum Error {
     #[error("Invalid binary data orientation for orientation {0}. Parser expected {1} values and got {2} values")]
     BinaryOrientationError(String, usize, usize),
 
+    // Data access
+    #[error("Could not read data: {0}")]
+    DataRead(String),
+    #[error("Channel {0} out of range for data with {1} channels")]
+    ChannelOutOfRange(usize, usize),
+    #[error("Sample range {0}..{1} out of range for data with {2} samples")]
+    SampleRangeOutOfRange(usize, usize, usize),
+    #[error("Chunk size has to be at least one sample")]
+    EmptyChunkSize,
+    #[error("Channel with {0} samples can not be split into {1} layers")]
+    LayerMismatch(usize, usize),
+    #[error("Complex data needs pairs of real and imaginary values, found {0} values")]
+    ComplexPairMismatch(usize),
+    #[error("Complex data types have to be read with BVComplexFile")]
+    ComplexDataType,
+    #[error("Only complex data types can be read with BVComplexFile")]
+    RealDataType,
+    #[error("Channel with {0} samples can not be split into segments of {1} samples")]
+    SegmentMismatch(usize, usize),
+    #[error("Segmented data needs SegmentDataPoints in the header")]
+    MissingSegmentDataPoints,
+    #[error("Layer {0} out of range for data with {1} layers")]
+    LayerOutOfRange(usize, usize),
+    #[error("Header expects {0} values per channel for all layers, found {1}")]
+    DataPointsMismatch(usize, usize),
+
+    // ASCII parser
+    #[error("Invalid ASCII data in line {0}: {1}")]
+    AsciiParserError(usize, String),
+
 
     // Channel scaling
     #[error("Mismatching channel data dimensions. Data has length: {0} while resolution info has length: {1}")]
@@ -2,42 +2,108 @@ pub mod parser;
 
 #[allow(unused_imports)]
 use parser::{
-    read_datfile::get_file_as_byte_vec,
-    parse_bytestring::{parse_bytestring_to_f32, parse_bytestring_to_i16, parse_bytestring_to_u16},
-    parse_orientation::{parse_multiplexed_data, parse_vectorized_data}
+    read_datfile::{get_file_as_byte_vec, read_as_byte_vec},
+    parse_bytestring::{parse_bytestring, parse_bytestring_to_f32, parse_bytestring_to_i16, parse_bytestring_to_u16},
+    parse_orientation::{parse_multiplexed_data, parse_vectorized_data},
+    parse_ascii::parse_ascii_data,
 };
 
 pub mod process;
 
+pub mod sample;
+
+pub mod native;
+
+pub mod layout;
+
+pub mod mapped;
+
+pub mod reader;
+
+pub mod chunks;
+
+pub mod matrix;
+
+pub mod complex;
+
+pub mod segments;
+
+use sample::Sample;
+
+use matrix::SampleMatrix;
+
 use process::scale_channels::scale_channels;
 
 use crate::bv_reader::header::parser::{
     parse_dataorientation::DataOrientation,
     parse_binaryformat::BinaryFormat,
+    parse_ascii_infos::AsciiInfo,
+    parse_header_encoding::decode_textcontent,
     parse_chan_info::ChannelInfo};
 
+use crate::bv_reader::header::BVheader;
+
 use crate::bv_reader::bv_error::Error;
 
+use std::io::Read;
+
 
 
 /// Stores the data as vector of channels, each containing the vector of samples
+///
+/// Samples are `f32` by default, use `f64` for the 32 and 64 bit binary formats.
 #[derive(Debug, PartialEq, Clone)]
-pub struct BVData {
+pub struct BVData<T: Sample = f32> {
     pub data_path: String,
-    pub data: Vec<Vec<f32>>, // 2D-Vector of channels
+    pub data: Vec<Vec<T>>, // 2D-Vector of channels
     pub num_chan: usize,
 }
 
 impl BVData {
+    /// Reads and decodes a binary data file into `f32` samples
+    ///
+    /// `INT_32`, `UINT_32` and `IEEE_FLOAT_64` data can not be held by `f32` without loss of precision
+    /// and return Error::LossyConversion, read them as `f64` with [BVData::from_file_as]:
+    /// ```
+    /// use crate::bvreader::bv_reader::{bv_error::Error, data::BVData};
+    /// use crate::bvreader::bv_reader::header::parser::{parse_binaryformat::BinaryFormat, parse_dataorientation::DataOrientation};
+    ///
+    /// let datapath = "src/bv_reader/data/testfiles/04_data_int32.eeg";
+    /// let output = BVData::from_file(datapath, 2, BinaryFormat::INT_32, DataOrientation::MULTIPLEXED, false);
+    /// assert_eq!(output, Err(Error::LossyConversion("INT_32".to_string(), "f32".to_string())));
+    ///
+    /// let bv_data = BVData::<f64>::from_file_as(datapath, 2, BinaryFormat::INT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+    /// assert_eq!(bv_data.data[0][1], 2147483647.);
+    /// ```
     pub fn from_file(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        BVData::from_file_as(datapath, num_channels, binary_format, orientation, use_big_endian)
+    }
+
+    /// Reads a data file with `DataFormat=ASCII`, returns the same channel layout as `BVData::from_file`
+    pub fn from_ascii_file(datapath: &str, num_channels: usize, ascii_info: &AsciiInfo, orientation: DataOrientation) -> Result<Self, Error> {
+        BVData::from_ascii_file_as(datapath, num_channels, ascii_info, orientation)
+    }
+}
+
+impl<T: Sample> BVData<T> {
+    /// Same as `BVData::from_file` but converts the samples to `T`
+    ///
+    /// Returns Error::LossyConversion if `T` can not hold the binary format without loss of precision
+    pub fn from_file_as(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
         let bytestring = get_file_as_byte_vec(datapath)?;
+        let mut bv_data = BVData::from_bytes_as(bytestring, num_channels, binary_format, orientation, use_big_endian)?;
+        bv_data.data_path = datapath.to_string();
+        Ok(bv_data)
+    }
 
-        let raw_data = match binary_format {
-            BinaryFormat::IEEE_FLOAT_32 => {parse_bytestring_to_f32(bytestring, use_big_endian)?},
-            BinaryFormat::INT_16 => {parse_bytestring_to_i16(bytestring, use_big_endian)?},
-            BinaryFormat::UINT_16 => {parse_bytestring_to_u16(bytestring, use_big_endian)?},
-            BinaryFormat::Unknown => {return Err(Error::InvalidBinaryFormat);}
-        };
+    /// Same as `BVData::from_file_as` but reads the binary data from any source, `data_path` stays empty
+    pub fn from_reader_as<R: Read>(reader: R, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        BVData::from_bytes_as(read_as_byte_vec(reader)?, num_channels, binary_format, orientation, use_big_endian)
+    }
+
+    /// Decodes binary data that is already in memory, `data_path` stays empty
+    pub fn from_bytes_as(bytestring: Vec<u8>, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        let raw_data = parse_bytestring::<T>(bytestring, binary_format, use_big_endian)?;
 
         let parsed_data = match orientation {
             DataOrientation::MULTIPLEXED => {parse_multiplexed_data(raw_data, num_channels)?},
@@ -46,14 +112,168 @@ impl BVData {
         };
         let num_channels = parsed_data.len();
         Ok(BVData{
-            data_path: datapath.to_string(),
+            data_path: String::new(),
+            data: parsed_data,
+            num_chan: num_channels,
+        })
+    }
+
+    /// Same as `BVData::from_ascii_file` but converts the samples to `T`
+    pub fn from_ascii_file_as(datapath: &str, num_channels: usize, ascii_info: &AsciiInfo, orientation: DataOrientation) -> Result<Self, Error> {
+        let bytestring = get_file_as_byte_vec(datapath)?;
+        let mut bv_data = BVData::from_ascii_bytes_as(&bytestring, num_channels, ascii_info, orientation)?;
+        bv_data.data_path = datapath.to_string();
+        Ok(bv_data)
+    }
+
+    /// Parses ASCII data that is already in memory, `data_path` stays empty
+    pub fn from_ascii_bytes_as(bytestring: &[u8], num_channels: usize, ascii_info: &AsciiInfo, orientation: DataOrientation) -> Result<Self, Error> {
+        let textcontent = decode_textcontent(bytestring);
+
+        let parsed_data = parse_ascii_data::<T>(&textcontent, ascii_info, num_channels, orientation)?;
+        let num_channels = parsed_data.len();
+        Ok(BVData{
+            data_path: String::new(),
             data: parsed_data,
             num_chan: num_channels,
         })
     }
 
-    pub fn scale_channels(&mut self, channel_info: &Vec<ChannelInfo>) -> Result<(), Error> {
+    /// Wraps a contiguous matrix into the nested layout, `data_path` stays empty
+    pub fn from_matrix(matrix: &SampleMatrix<T>) -> Self {
+        BVData{
+            data_path: String::new(),
+            data: matrix.to_nested(),
+            num_chan: matrix.num_channels(),
+        }
+    }
+
+    /// Copies the channels into one contiguous buffer, returns an error if they differ in length
+    pub fn to_matrix(&self) -> Result<SampleMatrix<T>, Error> {
+        SampleMatrix::from_nested(&self.data)
+    }
+
+    pub fn scale_channels(&mut self, channel_info: &[ChannelInfo]) -> Result<(), Error> {
         scale_channels(&mut self.data, channel_info)?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Splits every channel into segments of `segment_len` samples without copying
+    pub fn segments(&self, segment_len: usize) -> Result<Vec<Vec<&[T]>>, Error> {
+        segments::split_segments(&self.data, segment_len)
+    }
+
+    /// Returns the number of data points per channel and layer
+    ///
+    /// Each layer is stored as a complete data set in the data file, the layers follow one after another.
+    /// Returns an error if the channels do not hold `Layers` times `DataPoints` values.
+    pub fn num_points(&self, header: &BVheader) -> Result<usize, Error> {
+        let num_layers = header.num_layers();
+        let chan_len = self.data.first().map_or(0, |channel| channel.len());
+        if let Some(channel) = self.data.iter().find(|channel| channel.len() != chan_len) {
+            return Err(Error::ChannelDataMalformed(chan_len, channel.len()))
+        }
+        if num_layers == 0 || chan_len % num_layers != 0 {return Err(Error::LayerMismatch(chan_len, num_layers))}
+
+        let num_points = chan_len / num_layers;
+        match header.data_points {
+            Some(data_points) if data_points != num_points => Err(Error::DataPointsMismatch(data_points * num_layers, chan_len)),
+            _ => Ok(num_points),
+        }
+    }
+
+    /// Returns the data points of one layer for every channel without copying
+    pub fn layer(&self, header: &BVheader, layer: usize) -> Result<Vec<&[T]>, Error> {
+        let num_points = self.num_points(header)?;
+        if layer >= header.num_layers() {return Err(Error::LayerOutOfRange(layer, header.num_layers()))}
+        (0..self.data.len())
+            .map(|channel| self.layer_points(header, channel, layer, num_points))
+            .collect()
+    }
+
+    /// Returns the time-frequency plane of one channel with one row per layer
+    pub fn time_frequency_plane(&self, header: &BVheader, channel: usize) -> Result<SampleMatrix<T>, Error> {
+        let num_points = self.num_points(header)?;
+        if channel >= self.data.len() {return Err(Error::ChannelOutOfRange(channel, self.data.len()))}
+        let mut plane = Vec::with_capacity(num_points * header.num_layers());
+        for layer in 0..header.num_layers() {
+            plane.extend_from_slice(self.layer_points(header, channel, layer, num_points)?);
+        }
+        SampleMatrix::from_vec(plane, header.num_layers())
+    }
+
+    fn layer_points(&self, header: &BVheader, channel: usize, layer: usize, num_points: usize) -> Result<&[T], Error> {
+        let layer_len = num_points * header.num_layers();
+        // decoded channel and offset of the first point, the data was demultiplexed as if it had a single layer
+        let (data_channel, offset) = match header.data_orientation {
+            DataOrientation::MULTIPLEXED => (channel, layer * num_points),
+            DataOrientation::VECTORIZED => {
+                let start = (layer * self.data.len() + channel) * num_points;
+                (start / layer_len, start % layer_len)
+            },
+            DataOrientation::Unknown => return Err(Error::InvalidDataOrientation),
+        };
+        let channel_data = self.data.get(data_channel).ok_or(Error::ChannelOutOfRange(channel, self.data.len()))?;
+        channel_data.get(offset..offset + num_points).ok_or(Error::ChannelDataMalformed(layer_len, channel_data.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::header::parser::parse_layers::LayerInfo;
+
+    fn layered() -> (BVheader, BVData) {
+        // 2 channels, 3 layers with 4 points each, value = 100 * layer + 10 * channel + point
+        let header = BVheader::from_file("src/bv_reader/data/testfiles/07_header_layers.vhdr").unwrap();
+        let data = BVData::from_file("src/bv_reader/data/testfiles/07_data_layers.dat", header.num_channels, header.binary_format, header.data_orientation, header.use_big_endian).unwrap();
+        (header, data)
+    }
+
+    #[test]
+    fn test_layer() {
+        let (header, input) = layered();
+        assert_eq!(input.num_points(&header), Ok(4));
+        let output = input.layer(&header, 1).unwrap();
+        let expected: Vec<&[f32]> = vec![&[100., 101., 102., 103.], &[110., 111., 112., 113.]];
+        assert_eq!(output, expected);
+        assert_eq!(input.layer(&header, 3), Err(Error::LayerOutOfRange(3, 3)));
+    }
+
+    #[test]
+    fn test_layer_vectorized() {
+        let (multiplexed_header, multiplexed) = layered();
+        let header = BVheader{data_orientation: DataOrientation::VECTORIZED, ..multiplexed_header.clone()};
+        // same values as the fixture, every layer stored channel by channel
+        let values: Vec<f32> = (0..3).flat_map(|layer| (0..2).flat_map(move |channel| (0..4).map(move |point| (100 * layer + 10 * channel + point) as f32))).collect();
+        let bytestring = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+        let input = BVData::from_bytes_as(bytestring, 2, BinaryFormat::IEEE_FLOAT_32, DataOrientation::VECTORIZED, false).unwrap();
+        let expected: Vec<&[f32]> = vec![&[200., 201., 202., 203.], &[210., 211., 212., 213.]];
+        assert_eq!(input.layer(&header, 2).unwrap(), expected);
+        assert_eq!(input.time_frequency_plane(&header, 1), multiplexed.time_frequency_plane(&multiplexed_header, 1));
+    }
+
+    #[test]
+    fn test_layer_mismatch() {
+        let (header, input) = layered();
+        let output = input.num_points(&BVheader{data_points: Some(5), ..header.clone()});
+        assert_eq!(output, Err(Error::DataPointsMismatch(15, 12)));
+        let output = input.layer(&BVheader{layer_info: Some(LayerInfo{num_layers: 5, ..header.layer_info.unwrap()}), ..header.clone()}, 0);
+        assert_eq!(output, Err(Error::LayerMismatch(12, 5)));
+        let output = input.layer(&BVheader{data_orientation: DataOrientation::Unknown, ..header}, 0);
+        assert_eq!(output, Err(Error::InvalidDataOrientation));
+    }
+
+    #[test]
+    fn test_time_frequency_plane() {
+        let (header, input) = layered();
+        let output = input.time_frequency_plane(&header, 1).unwrap();
+        assert_eq!(output.num_channels(), 3);
+        assert_eq!(output.channel(0), Some(&[10., 11., 12., 13.][..]));
+        assert_eq!(output.time_point(2).unwrap().copied().collect::<Vec<f32>>(), vec![12., 112., 212.]);
+        assert_eq!(header.layer_info.unwrap().frequencies(), vec![2., 4., 8.]);
+        assert_eq!(input.time_frequency_plane(&header, 2), Err(Error::ChannelOutOfRange(2, 2)));
+    }
+
+
+}
@@ -0,0 +1,144 @@
+//!
+//! This module contains the streaming iterator over binary data files
+//!
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::marker::PhantomData;
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::data::parser::{
+    parse_bytestring::parse_bytestring,
+    parse_orientation::parse_multiplexed_data,
+};
+use crate::bv_reader::header::parser::{
+    parse_binaryformat::BinaryFormat,
+    parse_dataorientation::DataOrientation,
+};
+
+/// Iterator over blocks of `chunk_samples` samples of all channels
+///
+/// Reads the source sequentially with constant memory, each item has the channel layout of `BVData::data`.
+/// The last block may contain less samples. Only supports MULTIPLEXED data, as VECTORIZED data
+/// stores each channel in one piece.
+#[derive(Debug)]
+pub struct BVDataChunks<R: BufRead, T: Sample = f32> {
+    reader: R,
+    num_channels: usize,
+    binary_format: BinaryFormat,
+    use_big_endian: bool,
+    buffer: Vec<u8>,
+    finished: bool,
+    sample_type: PhantomData<T>,
+}
+
+impl<T: Sample> BVDataChunks<BufReader<File>, T> {
+    pub fn from_file(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool, chunk_samples: usize) -> Result<Self, Error> {
+        let file = File::open(datapath).map_err(|_| Error::FileOpen(datapath.to_string()))?;
+        BVDataChunks::new(BufReader::new(file), num_channels, binary_format, orientation, use_big_endian, chunk_samples)
+    }
+}
+
+impl<R: BufRead, T: Sample> BVDataChunks<R, T> {
+    /// Returns an error for VECTORIZED data, unknown or lossy binary formats, zero channels and empty chunks
+    pub fn new(reader: R, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool, chunk_samples: usize) -> Result<Self, Error> {
+        if orientation != DataOrientation::MULTIPLEXED {return Err(Error::InvalidDataOrientation)}
+        let bytes_per_sample = binary_format.bytes_per_sample().ok_or(Error::InvalidBinaryFormat)?;
+        if !T::is_lossless_for(binary_format) {
+            return Err(Error::LossyConversion(binary_format.as_str().to_string(), T::NAME.to_string()))
+        }
+        if num_channels == 0 {return Err(Error::NoChannels)}
+        if chunk_samples == 0 {return Err(Error::EmptyChunkSize)}
+
+        Ok(BVDataChunks{
+            reader,
+            num_channels,
+            binary_format,
+            use_big_endian,
+            buffer: vec![0; chunk_samples * num_channels * bytes_per_sample],
+            finished: false,
+            sample_type: PhantomData,
+        })
+    }
+
+    /// Fills the buffer as far as possible, returns the number of bytes read
+    fn fill_buffer(&mut self) -> Result<usize, Error> {
+        let mut filled = 0;
+        while filled < self.buffer.len() {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(0) => break,
+                Ok(len) => filled += len,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(Error::DataRead(err.to_string())),
+            }
+        }
+        Ok(filled)
+    }
+}
+
+impl<R: BufRead, T: Sample> Iterator for BVDataChunks<R, T> {
+    type Item = Result<Vec<Vec<T>>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {return None}
+
+        let filled = match self.fill_buffer() {
+            Ok(filled) => filled,
+            Err(err) => {self.finished = true; return Some(Err(err))},
+        };
+        if filled < self.buffer.len() {self.finished = true}
+        if filled == 0 {return None}
+
+        let raw_data = match parse_bytestring::<T>(self.buffer[..filled].to_vec(), self.binary_format, self.use_big_endian) {
+            Ok(raw_data) => raw_data,
+            Err(err) => {self.finished = true; return Some(Err(err))},
+        };
+        Some(parse_multiplexed_data(raw_data, self.num_channels))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::bv_reader::data::BVData;
+
+    #[test]
+    fn test_data_chunks() {
+        let input = "src/bv_reader/data/testfiles/01_data.eeg";
+        let full = BVData::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let chunks: Vec<Vec<Vec<f32>>> = BVDataChunks::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false, 30).unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[3][0].len(), 10);
+        assert_eq!(chunks[1][70], full.data[70][30..60].to_vec());
+    }
+
+    #[test]
+    fn test_data_chunks_malformed() {
+        // 2 channels, the last sample point is missing one channel
+        let input: Vec<u8> = [1i16, 2, 3].iter().flat_map(|x| x.to_le_bytes()).collect();
+        let mut chunks = BVDataChunks::<_, f32>::new(Cursor::new(input), 2, BinaryFormat::INT_16, DataOrientation::MULTIPLEXED, false, 4).unwrap();
+        assert_eq!(chunks.next(), Some(Err(Error::BinaryOrientationError("MULTIPLEXED".to_string(), 3, 4))));
+        assert_eq!(chunks.next(), Option::None);
+    }
+
+    #[test]
+    fn test_data_chunks_vectorized() {
+        let output = BVDataChunks::<_, f32>::new(Cursor::new(vec![0; 4]), 2, BinaryFormat::INT_16, DataOrientation::VECTORIZED, false, 4);
+        assert_eq!(output.map(|_| ()), Err(Error::InvalidDataOrientation));
+    }
+
+    #[test]
+    fn test_data_chunks_empty() {
+        let output = BVDataChunks::<_, f32>::new(Cursor::new(vec![0; 4]), 0, BinaryFormat::INT_16, DataOrientation::MULTIPLEXED, false, 4);
+        assert_eq!(output.map(|_| ()), Err(Error::NoChannels));
+        let output = BVDataChunks::<_, f32>::new(Cursor::new(vec![0; 4]), 2, BinaryFormat::INT_16, DataOrientation::MULTIPLEXED, false, 0);
+        assert_eq!(output.map(|_| ()), Err(Error::EmptyChunkSize));
+    }
+
+}
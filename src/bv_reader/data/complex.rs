@@ -0,0 +1,181 @@
+//!
+//! This module contains the decoding of complex data, e.g. FFT or wavelet coefficients exported by Analyzer
+//!
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::data::parser::{
+    read_datfile::get_file_as_byte_vec,
+    parse_bytestring::parse_bytestring,
+    parse_orientation::{parse_multiplexed_data, parse_vectorized_data},
+};
+use crate::bv_reader::header::parser::{
+    parse_binaryformat::BinaryFormat,
+    parse_dataorientation::DataOrientation,
+    parse_chan_info::ChannelInfo,
+};
+
+/// One complex value, stored as real and imaginary part in the data file
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct Complex<T: Sample = f32> {
+    pub re: T,
+    pub im: T,
+}
+
+impl<T: Sample> Complex<T> {
+    /// Returns the absolute value `sqrt(re² + im²)`
+    pub fn magnitude(&self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// Returns the angle in radians within `[-π, π]`
+    pub fn phase(&self) -> T {
+        self.im.atan2(self.re)
+    }
+}
+
+/// Stores complex data as vector of channels, each containing the vector of values
+///
+/// Each value is a real/imaginary pair in the binary file, the pair counts as one sample point.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BVComplexData<T: Sample = f32> {
+    pub data_path: String,
+    pub data: Vec<Vec<Complex<T>>>,
+    pub num_chan: usize,
+}
+
+impl BVComplexData {
+    pub fn from_file(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        BVComplexData::from_file_as(datapath, num_channels, binary_format, orientation, use_big_endian)
+    }
+}
+
+impl<T: Sample> BVComplexData<T> {
+    /// Same as `BVComplexData::from_file` but converts the values to `T`
+    pub fn from_file_as(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        let bytestring = get_file_as_byte_vec(datapath)?;
+        let mut bv_data = BVComplexData::from_bytes_as(bytestring, num_channels, binary_format, orientation, use_big_endian)?;
+        bv_data.data_path = datapath.to_string();
+        Ok(bv_data)
+    }
+
+    /// Decodes complex binary data that is already in memory, `data_path` stays empty
+    ///
+    /// Every value is read as a real part directly followed by its imaginary part. This layout follows the
+    /// format description and has not been checked against a file exported with a complex data type.
+    pub fn from_bytes_as(bytestring: Vec<u8>, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        if num_channels == 0 {return Err(Error::NoChannels)}
+        let raw_data = parse_bytestring::<T>(bytestring, binary_format, use_big_endian)?;
+        if raw_data.len() % 2 != 0 {return Err(Error::ComplexPairMismatch(raw_data.len()))}
+        let values: Vec<Complex<T>> = raw_data.chunks_exact(2)
+            .map(|pair| Complex{re: pair[0], im: pair[1]})
+            .collect();
+
+        let parsed_data = match orientation {
+            DataOrientation::MULTIPLEXED => {parse_multiplexed_data(values, num_channels)?},
+            DataOrientation::VECTORIZED => {parse_vectorized_data(values, num_channels)?},
+            DataOrientation::Unknown => {return Err(Error::InvalidDataOrientation);}
+        };
+        let num_channels = parsed_data.len();
+        Ok(BVComplexData{
+            data_path: String::new(),
+            data: parsed_data,
+            num_chan: num_channels,
+        })
+    }
+
+    /// Returns the magnitude of every value of a channel
+    pub fn magnitude(&self, channel: usize) -> Option<Vec<T>> {
+        Some(self.data.get(channel)?.iter().map(|value| value.magnitude()).collect())
+    }
+
+    /// Returns the phase in radians of every value of a channel
+    pub fn phase(&self, channel: usize) -> Option<Vec<T>> {
+        Some(self.data.get(channel)?.iter().map(|value| value.phase()).collect())
+    }
+
+    /// Iterates over the real parts of a channel without copying
+    pub fn real(&self, channel: usize) -> Option<impl Iterator<Item = T> + '_> {
+        Some(self.data.get(channel)?.iter().map(|value| value.re))
+    }
+
+    /// Iterates over the imaginary parts of a channel without copying
+    pub fn imag(&self, channel: usize) -> Option<impl Iterator<Item = T> + '_> {
+        Some(self.data.get(channel)?.iter().map(|value| value.im))
+    }
+
+    /// Multiplies real and imaginary part with the resolution of the channel
+    pub fn scale_channels(&mut self, channel_info: &[ChannelInfo]) -> Result<(), Error> {
+        if self.data.len() != channel_info.len() {return Err(Error::ChannelDataMismatch(self.data.len(), channel_info.len()))}
+        for (channel, info) in self.data.iter_mut().zip(channel_info) {
+            let resolution = T::from_f32(info.resolution);
+            for value in channel.iter_mut() {
+                value.re *= resolution;
+                value.im *= resolution;
+            }
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytestring(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_complex() {
+        let input = Complex{re: 3f32, im: -4.};
+        assert_eq!(input.magnitude(), 5.);
+        assert_eq!(input.phase(), (-4f32).atan2(3.));
+        assert_eq!(Complex{re: 0f64, im: 1.}.phase(), std::f64::consts::FRAC_PI_2);
+    }
+
+    #[test]
+    fn test_from_bytes_multiplexed() {
+        // point 1: ch1 = 1+2i, ch2 = 3+4i; point 2: ch1 = 5+6i, ch2 = 7+8i
+        let input = bytestring(&[1., 2., 3., 4., 5., 6., 7., 8.]);
+        let output = BVComplexData::<f32>::from_bytes_as(input, 2, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let expected = vec![
+            vec![Complex{re: 1., im: 2.}, Complex{re: 5., im: 6.}],
+            vec![Complex{re: 3., im: 4.}, Complex{re: 7., im: 8.}],
+        ];
+        assert_eq!(output.data, expected);
+        assert_eq!(output.num_chan, 2);
+        assert_eq!(output.real(1).unwrap().collect::<Vec<f32>>(), vec![3., 7.]);
+        assert_eq!(output.imag(0).unwrap().collect::<Vec<f32>>(), vec![2., 6.]);
+        assert!(output.real(2).is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_vectorized() {
+        let input = bytestring(&[3., 4., 0., 2., 6., 8., 1., 0.]);
+        let output = BVComplexData::<f64>::from_bytes_as(input, 2, BinaryFormat::IEEE_FLOAT_32, DataOrientation::VECTORIZED, false).unwrap();
+        assert_eq!(output.magnitude(0), Some(vec![5., 2.]));
+        assert_eq!(output.magnitude(1), Some(vec![10., 1.]));
+        assert_eq!(output.phase(1).unwrap()[1], 0.);
+    }
+
+    #[test]
+    fn test_from_bytes_odd() {
+        let input = bytestring(&[1., 2., 3.]);
+        let output = BVComplexData::<f32>::from_bytes_as(input, 1, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false);
+        let expected = Err(Error::ComplexPairMismatch(3));
+        assert_eq!(output, expected);
+
+        let output = BVComplexData::<f32>::from_bytes_as(bytestring(&[1., 2.]), 0, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false);
+        assert_eq!(output, Err(Error::NoChannels));
+    }
+
+    #[test]
+    fn test_scale_channels() {
+        let mut input = BVComplexData::<f32>::from_bytes_as(bytestring(&[2., -4.]), 1, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        input.scale_channels(&[ChannelInfo{resolution: 0.5, ..Default::default()}]).unwrap();
+        assert_eq!(input.data, vec![vec![Complex{re: 1., im: -2.}]]);
+    }
+
+}
@@ -0,0 +1,106 @@
+//!
+//! This module contains the byte layout of binary data files
+//!
+
+use std::ops::Range;
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::header::parser::{
+    parse_binaryformat::BinaryFormat,
+    parse_dataorientation::DataOrientation,
+};
+
+/// Position of every sample inside of a binary data file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataLayout {
+    pub num_channels: usize,
+    pub num_samples: usize,
+    pub binary_format: BinaryFormat,
+    pub orientation: DataOrientation,
+    pub use_big_endian: bool,
+}
+
+impl DataLayout {
+    /// Derives the number of samples per channel from the size of the data file
+    ///
+    /// Returns an error if the size is not a multiple of one sample point over all channels
+    pub fn from_byte_len(byte_len: usize, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        let bytes_per_sample = binary_format.bytes_per_sample().ok_or(Error::InvalidBinaryFormat)?;
+        if orientation == DataOrientation::Unknown {return Err(Error::InvalidDataOrientation)}
+        if byte_len == 0 {return Err(Error::EmptyBinary)}
+        if num_channels == 0 {return Err(Error::NoChannels)}
+
+        let bytes_per_point = bytes_per_sample * num_channels;
+        if byte_len % bytes_per_point != 0 {
+            return Err(Error::BinaryParserError(binary_format.as_str().to_string(), byte_len - byte_len % bytes_per_point, byte_len))
+        }
+
+        Ok(DataLayout{
+            num_channels,
+            num_samples: byte_len / bytes_per_point,
+            binary_format,
+            orientation,
+            use_big_endian,
+        })
+    }
+
+    pub fn bytes_per_sample(&self) -> usize {
+        self.binary_format.bytes_per_sample().unwrap_or_default()
+    }
+
+    /// Returns the offset of the first byte of a sample
+    pub fn byte_offset(&self, channel: usize, sample: usize) -> usize {
+        let sample_idx = match self.orientation {
+            DataOrientation::VECTORIZED => channel * self.num_samples + sample,
+            _ => sample * self.num_channels + channel,
+        };
+        sample_idx * self.bytes_per_sample()
+    }
+
+    /// Returns an error if a channel or the sample range is outside of the data
+    pub fn check_window(&self, channels: &[usize], samples: &Range<usize>) -> Result<(), Error> {
+        if let Some(channel) = channels.iter().find(|channel| **channel >= self.num_channels) {
+            return Err(Error::ChannelOutOfRange(*channel, self.num_channels))
+        }
+        if samples.start > samples.end || samples.end > self.num_samples {
+            return Err(Error::SampleRangeOutOfRange(samples.start, samples.end, self.num_samples))
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_layout() {
+        let output = DataLayout::from_byte_len(28400, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        assert_eq!(output.num_samples, 100);
+        assert_eq!(output.byte_offset(2, 1), (71 + 2) * 4);
+
+        let output = DataLayout{orientation: DataOrientation::VECTORIZED, ..output};
+        assert_eq!(output.byte_offset(2, 1), (2 * 100 + 1) * 4);
+    }
+
+    #[test]
+    fn test_data_layout_malformed() {
+        let output = DataLayout::from_byte_len(10, 2, BinaryFormat::INT_16, DataOrientation::MULTIPLEXED, false);
+        let expected = Err(Error::BinaryParserError("INT_16".to_string(), 8, 10));
+        assert_eq!(output, expected);
+
+        let output = DataLayout::from_byte_len(10, 0, BinaryFormat::INT_16, DataOrientation::MULTIPLEXED, false);
+        let expected = Err(Error::NoChannels);
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_check_window() {
+        let layout = DataLayout::from_byte_len(40, 2, BinaryFormat::INT_16, DataOrientation::MULTIPLEXED, false).unwrap();
+        assert_eq!(layout.check_window(&[0, 1], &(0..10)), Ok(()));
+        assert_eq!(layout.check_window(&[2], &(0..10)), Err(Error::ChannelOutOfRange(2, 2)));
+        assert_eq!(layout.check_window(&[0], &(5..11)), Err(Error::SampleRangeOutOfRange(5, 11, 10)));
+    }
+
+}
@@ -0,0 +1,119 @@
+//!
+//! This module contains the memory-mapped access to binary data files
+//!
+
+use std::fs::File;
+use std::ops::Range;
+
+use memmap2::Mmap;
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::layout::DataLayout;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::data::parser::parse_bytestring::decode_sample;
+use crate::bv_reader::header::parser::{
+    parse_binaryformat::BinaryFormat,
+    parse_dataorientation::DataOrientation,
+};
+
+/// Memory-mapped binary data file, samples are only decoded when requested
+///
+/// The operating system pages the file in on access, so peak memory stays at the size of the requested windows.
+/// The file must not be modified while it is mapped.
+#[derive(Debug)]
+pub struct MappedBVData {
+    pub data_path: String,
+    pub layout: DataLayout,
+    mmap: Mmap,
+}
+
+impl MappedBVData {
+    pub fn from_file(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        let file = File::open(datapath).map_err(|_| Error::FileOpen(datapath.to_string()))?;
+        let metadata = file.metadata().map_err(|_| Error::FileMeta(datapath.to_string()))?;
+        let layout = DataLayout::from_byte_len(metadata.len() as usize, num_channels, binary_format, orientation, use_big_endian)?;
+
+        // Safety: the mapping is read-only, modifying the file from another process while mapped is undefined behaviour
+        let mmap = unsafe {Mmap::map(&file)}.map_err(|_| Error::FileRead(datapath.to_string()))?;
+
+        Ok(MappedBVData{
+            data_path: datapath.to_string(),
+            layout,
+            mmap,
+        })
+    }
+
+    pub fn num_channels(&self) -> usize {
+        self.layout.num_channels
+    }
+
+    /// Number of samples per channel
+    pub fn num_samples(&self) -> usize {
+        self.layout.num_samples
+    }
+
+    /// Decodes the raw values of the selected channels in the sample range, without scaling
+    ///
+    /// Returns one vector per requested channel in the requested order.
+    /// Returns Error::LossyConversion if `T` can not hold the binary format without loss of precision.
+    pub fn read_window<T: Sample>(&self, channels: &[usize], samples: Range<usize>) -> Result<Vec<Vec<T>>, Error> {
+        let layout = &self.layout;
+        if !T::is_lossless_for(layout.binary_format) {
+            return Err(Error::LossyConversion(layout.binary_format.as_str().to_string(), T::NAME.to_string()))
+        }
+        layout.check_window(channels, &samples)?;
+
+        let bytes_per_sample = layout.bytes_per_sample();
+        channels.iter()
+            .map(|channel| samples.clone()
+                .map(|sample| {
+                    let offset = layout.byte_offset(*channel, sample);
+                    decode_sample(&self.mmap[offset..offset + bytes_per_sample], layout.binary_format, layout.use_big_endian)
+                })
+                .collect())
+            .collect()
+    }
+
+    /// Decodes all samples of a single channel
+    pub fn read_channel<T: Sample>(&self, channel: usize) -> Result<Vec<T>, Error> {
+        let mut window = self.read_window(&[channel], 0..self.num_samples())?;
+        Ok(window.pop().unwrap_or_default())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::data::BVData;
+
+    #[test]
+    fn test_read_window() {
+        let input = "src/bv_reader/data/testfiles/01_data.eeg";
+        let mapped = MappedBVData::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let full = BVData::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        assert_eq!(mapped.num_samples(), 100);
+
+        let output: Vec<Vec<f32>> = mapped.read_window(&[70, 3], 10..20).unwrap();
+        assert_eq!(output, vec![full.data[70][10..20].to_vec(), full.data[3][10..20].to_vec()]);
+        assert_eq!(mapped.read_channel::<f32>(5).unwrap(), full.data[5]);
+    }
+
+    #[test]
+    fn test_read_window_out_of_range() {
+        let input = "src/bv_reader/data/testfiles/01_data.eeg";
+        let mapped = MappedBVData::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let output = mapped.read_window::<f32>(&[71], 0..1);
+        let expected = Err(Error::ChannelOutOfRange(71, 71));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_read_window_lossy() {
+        let input = "src/bv_reader/data/testfiles/04_data_int32.eeg";
+        let mapped = MappedBVData::from_file(input, 2, BinaryFormat::INT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        assert!(mapped.read_window::<f32>(&[0], 0..1).is_err());
+        assert_eq!(mapped.read_window::<f64>(&[1], 1..3).unwrap(), vec![vec![-2147483648., 3.]]);
+    }
+
+}
@@ -0,0 +1,249 @@
+//!
+//! This module contains the contiguous channels x samples storage
+//!
+
+use std::ops::Range;
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::header::parser::parse_chan_info::ChannelInfo;
+
+/// Stores the samples of all channels in one buffer, channel after channel
+///
+/// Every channel has the same number of samples. Channels are borrowed as slices,
+/// time points as strided iterators over the buffer.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SampleMatrix<T: Sample = f32> {
+    data: Vec<T>,
+    num_channels: usize,
+    num_samples: usize,
+}
+
+impl<T: Sample> SampleMatrix<T> {
+    /// Returns a matrix filled with `T::default()`
+    pub fn new(num_channels: usize, num_samples: usize) -> Self {
+        SampleMatrix{
+            data: vec![T::default(); num_channels * num_samples],
+            num_channels,
+            num_samples,
+        }
+    }
+
+    /// Takes a buffer of `num_channels` channels stored one after another
+    pub fn from_vec(data: Vec<T>, num_channels: usize) -> Result<Self, Error> {
+        if num_channels == 0 {
+            if !data.is_empty() {return Err(Error::ChannelDataMismatch(0, data.len()))}
+            return Ok(SampleMatrix::default())
+        }
+        if data.len() % num_channels != 0 {return Err(Error::ChannelDataMismatch(data.len(), num_channels))}
+        Ok(SampleMatrix{
+            num_samples: data.len() / num_channels,
+            data,
+            num_channels,
+        })
+    }
+
+    /// Copies the nested layout of [crate::bv_reader::data::BVData], channels must have equal length
+    pub fn from_nested(data: &[Vec<T>]) -> Result<Self, Error> {
+        let num_samples = data.first().map_or(0, |channel| channel.len());
+        let mut buffer = Vec::with_capacity(data.len() * num_samples);
+        for channel in data {
+            if channel.len() != num_samples {return Err(Error::ChannelDataMalformed(num_samples, channel.len()))}
+            buffer.extend_from_slice(channel);
+        }
+        Ok(SampleMatrix{
+            data: buffer,
+            num_channels: data.len(),
+            num_samples,
+        })
+    }
+
+    /// Copies the samples back into a vector of channels
+    pub fn to_nested(&self) -> Vec<Vec<T>> {
+        self.channels().map(|channel| channel.to_vec()).collect()
+    }
+
+    pub fn num_channels(&self) -> usize {
+        self.num_channels
+    }
+
+    pub fn num_samples(&self) -> usize {
+        self.num_samples
+    }
+
+    /// Returns the whole buffer, channel after channel
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    pub fn get(&self, channel: usize, sample: usize) -> Option<T> {
+        self.channel(channel)?.get(sample).copied()
+    }
+
+    pub fn channel(&self, channel: usize) -> Option<&[T]> {
+        if channel >= self.num_channels {return None}
+        let start = channel * self.num_samples;
+        Some(&self.data[start..start + self.num_samples])
+    }
+
+    pub fn channel_mut(&mut self, channel: usize) -> Option<&mut [T]> {
+        if channel >= self.num_channels {return None}
+        let start = channel * self.num_samples;
+        Some(&mut self.data[start..start + self.num_samples])
+    }
+
+    /// Iterates over all channels as slices
+    pub fn channels(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.num_channels).map(|channel| &self.data[channel * self.num_samples..(channel + 1) * self.num_samples])
+    }
+
+    /// Iterates over the value of every channel at one time point
+    pub fn time_point(&self, sample: usize) -> Option<impl Iterator<Item = &T>> {
+        if sample >= self.num_samples {return None}
+        Some(self.data[sample..].iter().step_by(self.num_samples))
+    }
+
+    /// Returns the samples of one channel within the range without copying
+    pub fn channel_window(&self, channel: usize, samples: Range<usize>) -> Result<&[T], Error> {
+        self.check_window(channel, &samples)?;
+        let start = channel * self.num_samples;
+        Ok(&self.data[start + samples.start..start + samples.end])
+    }
+
+    /// Copies the range of samples of all channels into a new matrix
+    pub fn slice_samples(&self, samples: Range<usize>) -> Result<Self, Error> {
+        self.check_window(0, &samples)?;
+        let mut buffer = Vec::with_capacity(self.num_channels * samples.len());
+        for channel in self.channels() {
+            buffer.extend_from_slice(&channel[samples.clone()]);
+        }
+        Ok(SampleMatrix{
+            data: buffer,
+            num_channels: self.num_channels,
+            num_samples: samples.len(),
+        })
+    }
+
+    /// Multiplies every channel with its resolution, see [crate::bv_reader::data::BVData::scale_channels]
+    pub fn scale_channels(&mut self, channel_info: &[ChannelInfo]) -> Result<(), Error> {
+        if self.num_channels != channel_info.len() {return Err(Error::ChannelDataMismatch(self.num_channels, channel_info.len()))}
+        let num_samples = self.num_samples.max(1);
+        for (channel, info) in self.data.chunks_exact_mut(num_samples).zip(channel_info) {
+            let resolution = T::from_f32(info.resolution);
+            for sample in channel.iter_mut() {
+                *sample *= resolution;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_window(&self, channel: usize, samples: &Range<usize>) -> Result<(), Error> {
+        if channel >= self.num_channels.max(1) {return Err(Error::ChannelOutOfRange(channel, self.num_channels))}
+        if samples.start > samples.end || samples.end > self.num_samples {
+            return Err(Error::SampleRangeOutOfRange(samples.start, samples.end, self.num_samples))
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> SampleMatrix {
+        SampleMatrix::from_nested(&[vec![1., 2., 3.], vec![4., 5., 6.]]).unwrap()
+    }
+
+    #[test]
+    fn test_from_nested() {
+        let output = matrix();
+        assert_eq!(output.num_channels(), 2);
+        assert_eq!(output.num_samples(), 3);
+        assert_eq!(output.as_slice(), &[1., 2., 3., 4., 5., 6.]);
+        assert_eq!(output.to_nested(), vec![vec![1., 2., 3.], vec![4., 5., 6.]]);
+    }
+
+    #[test]
+    fn test_from_nested_malformed() {
+        let output = SampleMatrix::<f32>::from_nested(&[vec![1., 2.], vec![3.]]);
+        let expected = Err(Error::ChannelDataMalformed(2, 1));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let output = SampleMatrix::from_vec(vec![1., 2., 3., 4., 5., 6.], 2).unwrap();
+        assert_eq!(output, matrix());
+
+        let output = SampleMatrix::<f32>::from_vec(vec![1., 2., 3.], 2);
+        let expected = Err(Error::ChannelDataMismatch(3, 2));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_views() {
+        let input = matrix();
+        assert_eq!(input.channel(1), Some(&[4., 5., 6.][..]));
+        assert_eq!(input.channel(2), Option::None);
+        assert_eq!(input.get(0, 2), Some(3.));
+        assert_eq!(input.time_point(1).unwrap().copied().collect::<Vec<f32>>(), vec![2., 5.]);
+        assert!(input.time_point(3).is_none());
+        assert_eq!(input.channels().count(), 2);
+    }
+
+    #[test]
+    fn test_channel_window() {
+        let input = matrix();
+        assert_eq!(input.channel_window(1, 1..3).unwrap(), &[5., 6.]);
+        assert_eq!(input.channel_window(2, 0..1), Err(Error::ChannelOutOfRange(2, 2)));
+        assert_eq!(input.channel_window(0, 2..4), Err(Error::SampleRangeOutOfRange(2, 4, 3)));
+    }
+
+    #[test]
+    fn test_slice_samples() {
+        let output = matrix().slice_samples(1..3).unwrap();
+        let expected = SampleMatrix::from_nested(&[vec![2., 3.], vec![5., 6.]]).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_scale_channels() {
+        let mut input = matrix();
+        let info = vec![
+            ChannelInfo{resolution: 0.5, ..Default::default()},
+            ChannelInfo{resolution: 2., ..Default::default()},
+        ];
+        input.scale_channels(&info).unwrap();
+        assert_eq!(input.to_nested(), vec![vec![0.5, 1., 1.5], vec![8., 10., 12.]]);
+
+        let output = input.scale_channels(&info[..1]);
+        let expected = Err(Error::ChannelDataMismatch(2, 1));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_bvdata_round_trip() {
+        use crate::bv_reader::data::BVData;
+        use crate::bv_reader::header::parser::{parse_binaryformat::BinaryFormat, parse_dataorientation::DataOrientation};
+
+        let input = BVData::from_file("src/bv_reader/data/testfiles/01_data.eeg", 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let output = input.to_matrix().unwrap();
+        assert_eq!((output.num_channels(), output.num_samples()), (71, 100));
+        assert_eq!(output.get(70, 99), Some(input.data[70][99]));
+        assert_eq!(BVData::from_matrix(&output).data, input.data);
+    }
+
+    #[test]
+    fn test_empty() {
+        let input = SampleMatrix::<f64>::from_nested(&[]).unwrap();
+        assert_eq!(input.num_channels(), 0);
+        assert_eq!(input.channels().count(), 0);
+        assert_eq!(SampleMatrix::<f64>::new(2, 0).to_nested(), vec![Vec::<f64>::new(), vec![]]);
+    }
+
+}
@@ -0,0 +1,238 @@
+//!
+//! This module contains the data storage that keeps samples in their on-disk type
+//!
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::data::parser::{
+    read_datfile::get_file_as_byte_vec,
+    parse_bytestring::{RawSample, decode_chunks},
+    parse_orientation::{parse_multiplexed_data, parse_vectorized_data},
+};
+use crate::bv_reader::header::parser::{
+    parse_binaryformat::BinaryFormat,
+    parse_dataorientation::DataOrientation,
+    parse_chan_info::ChannelInfo,
+};
+
+/// Vector of channels in the binary format of the data file
+#[derive(Debug, PartialEq, Clone)]
+pub enum NativeSamples {
+    I16(Vec<Vec<i16>>),
+    U16(Vec<Vec<u16>>),
+    I32(Vec<Vec<i32>>),
+    U32(Vec<Vec<u32>>),
+    F32(Vec<Vec<f32>>),
+    F64(Vec<Vec<f64>>),
+}
+
+impl NativeSamples {
+    pub fn binary_format(&self) -> BinaryFormat {
+        match self {
+            NativeSamples::I16(_) => BinaryFormat::INT_16,
+            NativeSamples::U16(_) => BinaryFormat::UINT_16,
+            NativeSamples::I32(_) => BinaryFormat::INT_32,
+            NativeSamples::U32(_) => BinaryFormat::UINT_32,
+            NativeSamples::F32(_) => BinaryFormat::IEEE_FLOAT_32,
+            NativeSamples::F64(_) => BinaryFormat::IEEE_FLOAT_64,
+        }
+    }
+
+    pub fn num_channels(&self) -> usize {
+        match self {
+            NativeSamples::I16(data) => data.len(),
+            NativeSamples::U16(data) => data.len(),
+            NativeSamples::I32(data) => data.len(),
+            NativeSamples::U32(data) => data.len(),
+            NativeSamples::F32(data) => data.len(),
+            NativeSamples::F64(data) => data.len(),
+        }
+    }
+
+    /// Returns the raw value of one sample converted to `T`, without scaling
+    pub fn get<T: Sample>(&self, channel: usize, sample: usize) -> Option<T> {
+        match self {
+            NativeSamples::I16(data) => data.get(channel)?.get(sample).map(|x| x.to_sample()),
+            NativeSamples::U16(data) => data.get(channel)?.get(sample).map(|x| x.to_sample()),
+            NativeSamples::I32(data) => data.get(channel)?.get(sample).map(|x| x.to_sample()),
+            NativeSamples::U32(data) => data.get(channel)?.get(sample).map(|x| x.to_sample()),
+            NativeSamples::F32(data) => data.get(channel)?.get(sample).map(|x| x.to_sample()),
+            NativeSamples::F64(data) => data.get(channel)?.get(sample).map(|x| x.to_sample()),
+        }
+    }
+
+    /// Converts the raw values to physical units: value * resolution
+    ///
+    /// Returns Error::LossyConversion if `T` can not hold the binary format without loss of precision.
+    pub fn to_physical<T: Sample>(&self, channel_info: &[ChannelInfo]) -> Result<Vec<Vec<T>>, Error> {
+        let binary_format = self.binary_format();
+        if !T::is_lossless_for(binary_format) {return Err(Error::LossyConversion(binary_format.as_str().to_string(), T::NAME.to_string()))}
+        if self.num_channels() != channel_info.len() {return Err(Error::ChannelDataMismatch(self.num_channels(), channel_info.len()))}
+
+        Ok(match self {
+            NativeSamples::I16(data) => scale_native(data, channel_info),
+            NativeSamples::U16(data) => scale_native(data, channel_info),
+            NativeSamples::I32(data) => scale_native(data, channel_info),
+            NativeSamples::U32(data) => scale_native(data, channel_info),
+            NativeSamples::F32(data) => scale_native(data, channel_info),
+            NativeSamples::F64(data) => scale_native(data, channel_info),
+        })
+    }
+
+    /// Encodes the samples in their binary format, the inverse of [BVNativeData::from_file]
+    pub fn to_bytestring(&self, orientation: DataOrientation, use_big_endian: bool) -> Result<Vec<u8>, Error> {
+        Ok(match self {
+            NativeSamples::I16(data) => encode_native(data, orientation, |x| if use_big_endian {x.to_be_bytes()} else {x.to_le_bytes()})?,
+            NativeSamples::U16(data) => encode_native(data, orientation, |x| if use_big_endian {x.to_be_bytes()} else {x.to_le_bytes()})?,
+            NativeSamples::I32(data) => encode_native(data, orientation, |x| if use_big_endian {x.to_be_bytes()} else {x.to_le_bytes()})?,
+            NativeSamples::U32(data) => encode_native(data, orientation, |x| if use_big_endian {x.to_be_bytes()} else {x.to_le_bytes()})?,
+            NativeSamples::F32(data) => encode_native(data, orientation, |x| if use_big_endian {x.to_be_bytes()} else {x.to_le_bytes()})?,
+            NativeSamples::F64(data) => encode_native(data, orientation, |x| if use_big_endian {x.to_be_bytes()} else {x.to_le_bytes()})?,
+        })
+    }
+}
+
+fn scale_native<N: RawSample, T: Sample>(data: &[Vec<N>], channel_info: &[ChannelInfo]) -> Vec<Vec<T>> {
+    data.iter()
+        .zip(channel_info)
+        .map(|(channel, info)| {
+            let resolution = T::from_f32(info.resolution);
+            channel.iter().map(|x| {
+                let mut value: T = x.to_sample();
+                value *= resolution;
+                value
+            }).collect()
+        })
+        .collect()
+}
+
+fn encode_native<N: Copy, const B: usize>(data: &[Vec<N>], orientation: DataOrientation, encode: impl Fn(N) -> [u8; B]) -> Result<Vec<u8>, Error> {
+    let chan_len = data.first().map_or(0, |channel| channel.len());
+    if let Some(channel) = data.iter().find(|channel| channel.len() != chan_len) {
+        return Err(Error::ChannelDataMalformed(chan_len, channel.len()))
+    }
+
+    let mut bytestring = Vec::with_capacity(data.len() * chan_len * B);
+    match orientation {
+        DataOrientation::MULTIPLEXED => {
+            for sample in 0..chan_len {
+                for channel in data {bytestring.extend(encode(channel[sample]))}
+            }
+        },
+        DataOrientation::VECTORIZED => {
+            for channel in data {
+                for value in channel {bytestring.extend(encode(*value))}
+            }
+        },
+        DataOrientation::Unknown => return Err(Error::InvalidDataOrientation),
+    }
+    Ok(bytestring)
+}
+
+
+/// Stores the data as vector of channels in the on-disk sample type
+///
+/// Use [NativeSamples::to_physical] to get scaled `f32` or `f64` values.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BVNativeData {
+    pub data_path: String,
+    pub samples: NativeSamples,
+    pub num_chan: usize,
+}
+
+impl BVNativeData {
+    pub fn from_file(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        if num_channels == 0 {return Err(Error::NoChannels)}
+        let bytestring = get_file_as_byte_vec(datapath)?;
+
+        let samples = match binary_format {
+            BinaryFormat::INT_16 => NativeSamples::I16(decode_native(&bytestring, num_channels, orientation, use_big_endian)?),
+            BinaryFormat::UINT_16 => NativeSamples::U16(decode_native(&bytestring, num_channels, orientation, use_big_endian)?),
+            BinaryFormat::INT_32 => NativeSamples::I32(decode_native(&bytestring, num_channels, orientation, use_big_endian)?),
+            BinaryFormat::UINT_32 => NativeSamples::U32(decode_native(&bytestring, num_channels, orientation, use_big_endian)?),
+            BinaryFormat::IEEE_FLOAT_32 => NativeSamples::F32(decode_native(&bytestring, num_channels, orientation, use_big_endian)?),
+            BinaryFormat::IEEE_FLOAT_64 => NativeSamples::F64(decode_native(&bytestring, num_channels, orientation, use_big_endian)?),
+            BinaryFormat::Unknown => return Err(Error::InvalidBinaryFormat),
+        };
+        Ok(BVNativeData{
+            data_path: datapath.to_string(),
+            num_chan: samples.num_channels(),
+            samples,
+        })
+    }
+
+    /// Converts the raw values to physical units: value * resolution
+    pub fn to_physical<T: Sample>(&self, channel_info: &[ChannelInfo]) -> Result<Vec<Vec<T>>, Error> {
+        self.samples.to_physical(channel_info)
+    }
+}
+
+fn decode_native<N: RawSample>(bytestring: &[u8], num_channels: usize, orientation: DataOrientation, use_big_endian: bool) -> Result<Vec<Vec<N>>, Error> {
+    let raw = decode_chunks(bytestring, use_big_endian, |x: N| x)?;
+    match orientation {
+        DataOrientation::MULTIPLEXED => parse_multiplexed_data(raw, num_channels),
+        DataOrientation::VECTORIZED => parse_vectorized_data(raw, num_channels),
+        DataOrientation::Unknown => Err(Error::InvalidDataOrientation),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_physical() {
+        let input = NativeSamples::I16(vec![vec![-2, 4], vec![1, i16::MAX]]);
+        let info = vec![
+            ChannelInfo{resolution: 0.5, ..Default::default()},
+            ChannelInfo{resolution: 2., ..Default::default()},
+        ];
+        let output: Vec<Vec<f32>> = input.to_physical(&info).unwrap();
+        let expected = vec![vec![-1., 2.], vec![2., 65534.]];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_to_physical_lossy() {
+        let input = NativeSamples::I32(vec![vec![i32::MAX]]);
+        let output = input.to_physical::<f32>(&[ChannelInfo::default()]);
+        let expected = Err(Error::LossyConversion("INT_32".to_string(), "f32".to_string()));
+        assert_eq!(output, expected);
+        assert_eq!(input.to_physical::<f64>(&[ChannelInfo::default()]), Ok(vec![vec![2147483647.]]));
+    }
+
+    #[test]
+    fn test_to_physical_mismatch() {
+        let input = NativeSamples::U16(vec![vec![1], vec![2]]);
+        let output = input.to_physical::<f64>(&[ChannelInfo::default()]);
+        let expected = Err(Error::ChannelDataMismatch(2, 1));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_to_bytestring() {
+        let input = NativeSamples::I16(vec![vec![1, 2], vec![-1, 3]]);
+        let output = input.to_bytestring(DataOrientation::MULTIPLEXED, false).unwrap();
+        let expected = vec![1, 0, 255, 255, 2, 0, 3, 0];
+        assert_eq!(output, expected);
+
+        let output = input.to_bytestring(DataOrientation::VECTORIZED, true).unwrap();
+        let expected = vec![0, 1, 0, 2, 255, 255, 0, 3];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_native_round_trip() {
+        let input = "src/bv_reader/data/testfiles/01_data.eeg";
+        let output = BVNativeData::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        assert_eq!(output.samples.binary_format(), BinaryFormat::IEEE_FLOAT_32);
+        assert_eq!(output.num_chan, 71);
+        let bytestring = output.samples.to_bytestring(DataOrientation::MULTIPLEXED, false).unwrap();
+        assert_eq!(bytestring, get_file_as_byte_vec(input).unwrap());
+
+        let output = BVNativeData::from_file(input, 0, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false);
+        assert_eq!(output, Err(Error::NoChannels));
+    }
+
+}
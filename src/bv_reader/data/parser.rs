@@ -1,3 +1,4 @@
 pub mod read_datfile;
 pub mod parse_bytestring;
 pub mod parse_orientation;
+pub mod parse_ascii;
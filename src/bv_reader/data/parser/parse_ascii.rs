@@ -0,0 +1,118 @@
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::header::parser::{
+    parse_ascii_infos::AsciiInfo,
+    parse_dataorientation::DataOrientation,
+};
+
+/// Parses the text of an ASCII data file into a vector of channels
+///
+/// Values are separated by whitespace. MULTIPLEXED files contain one sample point per line,
+/// VECTORIZED files one channel per line. Empty lines are ignored.
+pub fn parse_ascii_data<T: Sample>(textcontent: &str, ascii_info: &AsciiInfo, num_chan: usize, orientation: DataOrientation) -> Result<Vec<Vec<T>>, Error> {
+
+    let rows = textcontent.lines()
+        .enumerate()
+        .skip(ascii_info.skip_lines)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line_idx, line)| parse_ascii_row(line, line_idx + 1, ascii_info));
+
+    match orientation {
+        DataOrientation::MULTIPLEXED => {
+            let mut channel_data: Vec<Vec<T>> = vec![vec![]; num_chan];
+            for (line_no, row) in rows {
+                let row = row?;
+                if row.len() != num_chan {
+                    return Err(Error::AsciiParserError(line_no, format!("expected {} values, found {}", num_chan, row.len())))
+                }
+                for (channel, value) in channel_data.iter_mut().zip(row) {
+                    channel.push(value);
+                }
+            }
+            Ok(channel_data)
+        },
+        DataOrientation::VECTORIZED => {
+            let mut channel_data: Vec<Vec<T>> = Vec::with_capacity(num_chan);
+            for (line_no, row) in rows {
+                let row = row?;
+                if channel_data.len() == num_chan {
+                    return Err(Error::AsciiParserError(line_no, format!("expected {} channels", num_chan)))
+                }
+                if channel_data.first().is_some_and(|first| first.len() != row.len()) {
+                    return Err(Error::AsciiParserError(line_no, format!("expected {} values, found {}", channel_data[0].len(), row.len())))
+                }
+                channel_data.push(row);
+            }
+            if channel_data.len() != num_chan {
+                return Err(Error::BinaryOrientationError("VECTORIZED".to_string(), num_chan, channel_data.len()))
+            }
+            Ok(channel_data)
+        },
+        DataOrientation::Unknown => Err(Error::InvalidDataOrientation),
+    }
+}
+
+/// Returns the 1-based line number along with the parsed values
+fn parse_ascii_row<T: Sample>(line: &str, line_no: usize, ascii_info: &AsciiInfo) -> (usize, Result<Vec<T>, Error>) {
+    let values = line.split_whitespace()
+        .skip(ascii_info.skip_columns)
+        .map(|value| parse_ascii_value(value, ascii_info.decimal_symbol)
+            .ok_or_else(|| Error::AsciiParserError(line_no, format!("invalid value {value}"))))
+        .collect();
+    (line_no, values)
+}
+
+/// Returns Option::None if the value is not a number
+pub fn parse_ascii_value<T: Sample>(value: &str, decimal_symbol: char) -> Option<T> {
+    let parsed = if decimal_symbol == '.' {
+        value.parse::<f64>()
+    } else {
+        value.replace(decimal_symbol, ".").parse::<f64>()
+    };
+    parsed.ok().map(T::from_f64)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ascii_multiplexed() {
+        let input = "Fp1     Fz\n1.5     -2\n3       4.25\n";
+        let ascii_info = AsciiInfo{skip_lines: 1, ..Default::default()};
+        let output: Vec<Vec<f32>> = parse_ascii_data(input, &ascii_info, 2, DataOrientation::MULTIPLEXED).unwrap();
+        let expected = vec![vec![1.5, 3.], vec![-2., 4.25]];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_ascii_vectorized() {
+        let input = "Fp1 1,5 -2 3\r\nFz 4 5,25 6\r\n";
+        let ascii_info = AsciiInfo{decimal_symbol: ',', skip_columns: 1, ..Default::default()};
+        let output: Vec<Vec<f64>> = parse_ascii_data(input, &ascii_info, 2, DataOrientation::VECTORIZED).unwrap();
+        let expected = vec![vec![1.5, -2., 3.], vec![4., 5.25, 6.]];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_ascii_invalid() {
+        let input = "1.5 -2\n3 x\n";
+        let output = parse_ascii_data::<f32>(input, &AsciiInfo::default(), 2, DataOrientation::MULTIPLEXED);
+        let expected = Err(Error::AsciiParserError(2, "invalid value x".to_string()));
+        assert_eq!(output, expected);
+
+        let input = "1.5 -2\n3\n";
+        let output = parse_ascii_data::<f32>(input, &AsciiInfo::default(), 2, DataOrientation::MULTIPLEXED);
+        let expected = Err(Error::AsciiParserError(2, "expected 2 values, found 1".to_string()));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_ascii_value() {
+        assert_eq!(parse_ascii_value::<f32>("-0,5", ','), Some(-0.5));
+        assert_eq!(parse_ascii_value::<f32>("1e3", '.'), Some(1000.));
+        assert_eq!(parse_ascii_value::<f32>("µV", '.'), Option::None);
+    }
+
+}
@@ -1,71 +1,112 @@
 
 use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::header::parser::parse_binaryformat::BinaryFormat;
 
 
-pub fn parse_bytestring_to_f32(bytestring: Vec<u8>, use_big_endian: bool) -> Result<Vec<f32>, Error> {
+/// Sample types of the binary formats, decoded from little or big endian bytes
+pub trait RawSample: Copy {
+    /// Name of the type used in error messages
+    const NAME: &'static str;
+    /// Number of bytes of one sample
+    const SIZE: usize;
 
-    let bytestring_len = bytestring.len();
+    /// Panics if `chunk` is not `SIZE` bytes long
+    fn from_bytes(chunk: &[u8], use_big_endian: bool) -> Self;
+    /// Converts the value with the matching `Sample::from_*` function
+    fn to_sample<T: Sample>(self) -> T;
+}
 
-    if bytestring_len == 0 {return Err(Error::EmptyBinary)}
-    const CHUNK_SIZE: usize = 4;
-    let mut result: Vec<f32> = Vec::with_capacity(bytestring_len / CHUNK_SIZE);
+macro_rules! impl_raw_sample {
+    ($raw:ty, $from:ident) => {
+        impl RawSample for $raw {
+            const NAME: &'static str = stringify!($raw);
+            const SIZE: usize = std::mem::size_of::<$raw>();
 
-    let mut byte_idx = 0;
-    while byte_idx + CHUNK_SIZE - 1 <= bytestring_len - 1 {
+            fn from_bytes(chunk: &[u8], use_big_endian: bool) -> Self {
+                if use_big_endian {<$raw>::from_be_bytes(format_byte_arr(chunk))} else {<$raw>::from_le_bytes(format_byte_arr(chunk))}
+            }
 
-        let new_head = byte_idx + CHUNK_SIZE;
-        let chunk: &[u8] = &bytestring[byte_idx..new_head];
-        let num = if use_big_endian {f32::from_be_bytes(format_4byte_arr(chunk))} else {f32::from_le_bytes(format_4byte_arr(chunk))}; 
-        result.push(f32::from(num));
+            fn to_sample<T: Sample>(self) -> T {T::$from(self)}
+        }
+    };
+}
 
-        byte_idx = new_head;
+impl_raw_sample!(i16, from_i16);
+impl_raw_sample!(u16, from_u16);
+impl_raw_sample!(i32, from_i32);
+impl_raw_sample!(u32, from_u32);
+impl_raw_sample!(f32, from_f32);
+impl_raw_sample!(f64, from_f64);
+
+
+/// Decodes the bytestring in the given binary format and converts every value to `T`
+///
+/// Returns Error::LossyConversion if `T` can not hold every value of the format, e.g. `INT_32` as `f32`
+pub fn parse_bytestring<T: Sample>(bytestring: Vec<u8>, binary_format: BinaryFormat, use_big_endian: bool) -> Result<Vec<T>, Error> {
+    if binary_format == BinaryFormat::Unknown {return Err(Error::InvalidBinaryFormat)}
+    if !T::is_lossless_for(binary_format) {return Err(Error::LossyConversion(binary_format.as_str().to_string(), T::NAME.to_string()))}
+
+    match binary_format {
+        BinaryFormat::INT_16 => decode_chunks(&bytestring, use_big_endian, i16::to_sample),
+        BinaryFormat::UINT_16 => decode_chunks(&bytestring, use_big_endian, u16::to_sample),
+        BinaryFormat::INT_32 => decode_chunks(&bytestring, use_big_endian, i32::to_sample),
+        BinaryFormat::UINT_32 => decode_chunks(&bytestring, use_big_endian, u32::to_sample),
+        BinaryFormat::IEEE_FLOAT_32 => decode_chunks(&bytestring, use_big_endian, f32::to_sample),
+        BinaryFormat::IEEE_FLOAT_64 => decode_chunks(&bytestring, use_big_endian, f64::to_sample),
+        BinaryFormat::Unknown => Err(Error::InvalidBinaryFormat),
     }
-    //assert_eq!(byte_idx, bytestring_len);
-    if byte_idx != bytestring_len {return Err(Error::BinaryParserError("f32".to_string(), byte_idx, bytestring_len))}
-    
-    Ok(result)
 }
 
-pub fn parse_bytestring_to_u16(bytestring: Vec<u8>, use_big_endian: bool) -> Result<Vec<f32>, Error> {
-
-    let bytestring_len = bytestring.len();
-    if bytestring_len == 0 {return Err(Error::EmptyBinary)}
-    const CHUNK_SIZE: usize = 2;
-    let mut result: Vec<f32> = Vec::with_capacity(bytestring_len / CHUNK_SIZE);
-    let mut byte_idx = 0;
+/// Decodes a single sample from the first bytes of `chunk`
+///
+/// Returns Error::BinaryParserError if the chunk is too short. Does not check for lossy conversions.
+pub fn decode_sample<T: Sample>(chunk: &[u8], binary_format: BinaryFormat, use_big_endian: bool) -> Result<T, Error> {
+    match binary_format {
+        BinaryFormat::INT_16 => decode_first::<i16, T>(chunk, use_big_endian),
+        BinaryFormat::UINT_16 => decode_first::<u16, T>(chunk, use_big_endian),
+        BinaryFormat::INT_32 => decode_first::<i32, T>(chunk, use_big_endian),
+        BinaryFormat::UINT_32 => decode_first::<u32, T>(chunk, use_big_endian),
+        BinaryFormat::IEEE_FLOAT_32 => decode_first::<f32, T>(chunk, use_big_endian),
+        BinaryFormat::IEEE_FLOAT_64 => decode_first::<f64, T>(chunk, use_big_endian),
+        BinaryFormat::Unknown => Err(Error::InvalidBinaryFormat),
+    }
+}
 
-    while byte_idx + CHUNK_SIZE - 1 <= bytestring_len - 1 {
-        let new_head = byte_idx + CHUNK_SIZE;
-        let chunk: &[u8] = &bytestring[byte_idx..new_head];
+fn decode_first<N: RawSample, T: Sample>(chunk: &[u8], use_big_endian: bool) -> Result<T, Error> {
+    let sample = chunk.get(..N::SIZE).ok_or_else(|| Error::BinaryParserError(N::NAME.to_string(), 0, chunk.len()))?;
+    Ok(N::from_bytes(sample, use_big_endian).to_sample())
+}
 
-        let num = if use_big_endian {u16::from_be_bytes(format_2byte_arr(chunk))} else {u16::from_le_bytes(format_2byte_arr(chunk))};
-        result.push(f32::from(num));
+pub fn parse_bytestring_to_f32(bytestring: Vec<u8>, use_big_endian: bool) -> Result<Vec<f32>, Error> {
+    parse_bytestring(bytestring, BinaryFormat::IEEE_FLOAT_32, use_big_endian)
+}
 
-        byte_idx = new_head;
-    }
-    if byte_idx != bytestring_len {return Err(Error::BinaryParserError("u16".to_string(), byte_idx, bytestring_len))}
-    Ok(result)
+pub fn parse_bytestring_to_u16(bytestring: Vec<u8>, use_big_endian: bool) -> Result<Vec<f32>, Error> {
+    parse_bytestring(bytestring, BinaryFormat::UINT_16, use_big_endian)
 }
 
 pub fn parse_bytestring_to_i16(bytestring: Vec<u8>, use_big_endian: bool) -> Result<Vec<f32>, Error> {
+    parse_bytestring(bytestring, BinaryFormat::INT_16, use_big_endian)
+}
+
+/// Splits the bytestring into samples of type `N` and converts each sample with `convert`
+///
+/// Returns Error::BinaryParserError if the length is not a multiple of the sample size
+pub fn decode_chunks<N: RawSample, T>(bytestring: &[u8], use_big_endian: bool, convert: impl Fn(N) -> T) -> Result<Vec<T>, Error> {
 
     let bytestring_len = bytestring.len();
     if bytestring_len == 0 {return Err(Error::EmptyBinary)}
-    const CHUNK_SIZE: usize = 2;
-    let mut result: Vec<f32> = vec![];
-    let mut byte_idx = 0;
 
-    while byte_idx + CHUNK_SIZE - 1 <= bytestring_len - 1 {
+    let chunks = bytestring.chunks_exact(N::SIZE);
+    let byte_idx = bytestring_len - chunks.remainder().len();
+    if byte_idx != bytestring_len {return Err(Error::BinaryParserError(N::NAME.to_string(), byte_idx, bytestring_len))}
 
-        let new_head = byte_idx + CHUNK_SIZE;
-        let chunk: &[u8] = &bytestring[byte_idx..new_head];
-        let num = if use_big_endian {i16::from_be_bytes(format_2byte_arr(chunk))} else {i16::from_le_bytes(format_2byte_arr(chunk))};
-        result.push(f32::from(num));
+    Ok(chunks.map(|chunk| convert(N::from_bytes(chunk, use_big_endian))).collect())
+}
 
-        byte_idx = new_head;
-    }
-    if byte_idx != bytestring_len {return Err(Error::BinaryParserError("i16".to_string(), byte_idx, bytestring_len))}
-    Ok(result)
+pub fn format_byte_arr<const N: usize>(chunk: &[u8]) -> [u8; N] {
+    chunk.try_into().expect("Not of matching size")
 }
 
 pub fn format_4byte_arr(chunk: &[u8]) -> [u8; 4] {
@@ -84,7 +125,7 @@ mod tests {
     #[test]
     fn test_parse_bytestring_f32() {
         let res = parse_bytestring_to_f32(vec![110, 80, 20, 30, 110, 80, 20, 30], false).unwrap();
-        let expected = vec![7.851687231645703e-21, 7.851687231645703e-21];
+        let expected = vec![7.851_687e-21, 7.851_687e-21];
         assert_eq!(res, expected)
     }
 
@@ -109,6 +150,54 @@ mod tests {
         assert_eq!(res, expected) 
     }
 
+    #[test]
+    fn test_parse_bytestring_i32() {
+        let input = i32::MAX.to_le_bytes().into_iter().chain((-7i32).to_le_bytes()).collect();
+        let res: Vec<f64> = parse_bytestring(input, BinaryFormat::INT_32, false).unwrap();
+        let expected = vec![2147483647., -7.];
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn test_parse_bytestring_u32_big_endian() {
+        let input = u32::MAX.to_be_bytes().to_vec();
+        let res: Vec<f64> = parse_bytestring(input, BinaryFormat::UINT_32, true).unwrap();
+        let expected = vec![4294967295.];
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn test_parse_bytestring_f64() {
+        let input = 0.1f64.to_le_bytes().into_iter().chain(1e300f64.to_le_bytes()).collect();
+        let res: Vec<f64> = parse_bytestring(input, BinaryFormat::IEEE_FLOAT_64, false).unwrap();
+        let expected = vec![0.1, 1e300];
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn test_parse_bytestring_lossy() {
+        let res = parse_bytestring::<f32>(vec![0; 8], BinaryFormat::IEEE_FLOAT_64, false);
+        let expected = Err(Error::LossyConversion("IEEE_FLOAT_64".to_string(), "f32".to_string()));
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn test_parse_bytestring_malformed() {
+        let res = parse_bytestring::<f64>(vec![0; 10], BinaryFormat::IEEE_FLOAT_64, false);
+        let expected = Err(Error::BinaryParserError("f64".to_string(), 8, 10));
+        assert_eq!(res, expected)
+    }
+
+    #[test]
+    fn test_decode_sample() {
+        let output: Result<f32, Error> = decode_sample(&[0xff, 0xfe, 1], BinaryFormat::INT_16, true);
+        assert_eq!(output, Ok(-2.));
+        let output: Result<f32, Error> = decode_sample(&[1], BinaryFormat::INT_16, true);
+        assert_eq!(output, Err(Error::BinaryParserError("i16".to_string(), 0, 1)));
+        let output: Result<f64, Error> = decode_sample(&[0; 8], BinaryFormat::Unknown, false);
+        assert_eq!(output, Err(Error::InvalidBinaryFormat));
+    }
+
     #[test]
     fn test_format_4byte_chunk() {
         let res = format_4byte_arr(&[12,12,12,12]);
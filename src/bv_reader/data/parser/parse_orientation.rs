@@ -1,14 +1,15 @@
 use crate::bv_reader::bv_error::Error;
 
-pub fn parse_multiplexed_data(multiplexed_data: Vec<f32>, num_chan: usize) -> Result<Vec<Vec<f32>>, Error> {
+pub fn parse_multiplexed_data<T>(multiplexed_data: Vec<T>, num_chan: usize) -> Result<Vec<Vec<T>>, Error> {
     
-    let mut channel_data: Vec<Vec<f32>> = Vec::with_capacity(num_chan); 
+    if num_chan == 0 {return Err(Error::NoChannels)}
+    let mut channel_data: Vec<Vec<T>> = Vec::with_capacity(num_chan); 
 
     let data_len = multiplexed_data.len();
     let chan_len = data_len / num_chan;
 
     for _ in 0..num_chan {
-        let onechannel: Vec<f32> = Vec::with_capacity(chan_len);
+        let onechannel: Vec<T> = Vec::with_capacity(chan_len);
         channel_data.push(onechannel);
     }
 
@@ -24,15 +25,16 @@ pub fn parse_multiplexed_data(multiplexed_data: Vec<f32>, num_chan: usize) -> Re
 }
 
 
-pub fn parse_vectorized_data(vectorized_data: Vec<f32>, num_chan: usize) -> Result<Vec<Vec<f32>>, Error> {
+pub fn parse_vectorized_data<T>(vectorized_data: Vec<T>, num_chan: usize) -> Result<Vec<Vec<T>>, Error> {
     
-    let mut channel_data: Vec<Vec<f32>> = Vec::with_capacity(num_chan); 
+    if num_chan == 0 {return Err(Error::NoChannels)}
+    let mut channel_data: Vec<Vec<T>> = Vec::with_capacity(num_chan); 
     
     let data_len = vectorized_data.len();
     let chan_len = data_len / num_chan;
 
     for _ in 0..num_chan {
-        let onechannel: Vec<f32> = Vec::with_capacity(chan_len);
+        let onechannel: Vec<T> = Vec::with_capacity(chan_len);
         channel_data.push(onechannel);
     }
 
@@ -76,13 +78,22 @@ mod tests {
 
     #[test]
     fn test_parse_multiplexed_empty() {
-        let res = parse_multiplexed_data(vec![], 2).unwrap();
+        let res: Vec<Vec<f32>> = parse_multiplexed_data(vec![], 2).unwrap();
         let expected: Vec<Vec<f32>>  = vec![vec![],vec![]];
         assert_eq!(res, expected) 
 
     }
 
 
+    #[test]
+    fn test_parse_no_channels() {
+        let res = parse_multiplexed_data(vec![110., 80.], 0);
+        let expected: Result<Vec<Vec<f32>>, Error> = Err(Error::NoChannels);
+        assert_eq!(res, expected);
+        let res = parse_vectorized_data(vec![110., 80.], 0);
+        assert_eq!(res, expected);
+    }
+
     #[test]
     fn test_parse_vectorized() {
         let res = parse_vectorized_data(vec![
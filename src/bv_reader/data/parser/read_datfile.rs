@@ -1,6 +1,6 @@
 
 use std::fs::{self, File};
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
 //use std::fmt::Error;
 
 use crate::bv_reader::bv_error::Error;
@@ -8,10 +8,10 @@ use crate::bv_reader::bv_error::Error;
 /// Reads file as vector of bytes
 pub fn get_file_as_byte_vec(filename: &str) -> Result<Vec<u8>, Error> {
 
-    let f = File::open(&filename);
+    let f = File::open(filename);
     if f.is_err() {return Err(Error::FileOpen(filename.to_string()));}
 
-    let metadata = fs::metadata(&filename);
+    let metadata = fs::metadata(filename);
     if metadata.is_err() {return Err(Error::FileMeta(filename.to_string()));}
 
     let mut f = f.unwrap();
@@ -24,11 +24,45 @@ pub fn get_file_as_byte_vec(filename: &str) -> Result<Vec<u8>, Error> {
     Ok(buffer)
 }
 
+/// Reads the source to the end as vector of bytes
+pub fn read_as_byte_vec<R: Read>(mut reader: R) -> Result<Vec<u8>, Error> {
+    let mut buffer = vec![];
+    reader.read_to_end(&mut buffer).map_err(|err| Error::DataRead(err.to_string()))?;
+    Ok(buffer)
+}
+
+/// Same as [read_as_byte_vec] but sizes the buffer from the remaining length of the source up front
+pub fn read_seekable_as_byte_vec<R: Read + Seek>(mut reader: R) -> Result<Vec<u8>, Error> {
+    let to_error = |err: std::io::Error| Error::DataRead(err.to_string());
+    let position = reader.stream_position().map_err(to_error)?;
+    let end = reader.seek(SeekFrom::End(0)).map_err(to_error)?;
+    reader.seek(SeekFrom::Start(position)).map_err(to_error)?;
+
+    let mut buffer = Vec::with_capacity(end.saturating_sub(position) as usize);
+    reader.read_to_end(&mut buffer).map_err(to_error)?;
+    Ok(buffer)
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_read_as_byte_vec() {
+        let res = read_as_byte_vec(std::io::Cursor::new(vec![1, 2, 3]));
+        assert_eq!(res.unwrap(), vec![1, 2, 3])
+    }
+
+    #[test]
+    fn test_read_seekable_as_byte_vec() {
+        let mut input = std::io::Cursor::new(vec![1, 2, 3, 4]);
+        input.set_position(1);
+        let res = read_seekable_as_byte_vec(input).unwrap();
+        assert_eq!(res, vec![2, 3, 4]);
+        assert_eq!(res.capacity(), 3);
+    }
+
     #[test]
     fn test_get_file_as_byte_vec_empty() {
         let res = get_file_as_byte_vec("src/bv_reader/data/testfiles/test_data_empty.txt");
@@ -4,24 +4,31 @@ use crate::bv_reader::header::parser::parse_chan_info::ChannelInfo;
 //use std::fmt::Error;
 
 use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
 
-pub fn scale_channels(data: &mut Vec<Vec<f32>>, info: &Vec<ChannelInfo>) -> Result<(), Error> {
+pub fn scale_channels<T: Sample>(data: &mut [Vec<T>], info: &[ChannelInfo]) -> Result<(), Error> {
     if data.len() != info.len() {return Err(Error::ChannelDataMismatch(data.len(), info.len()))}; // not the same amount of channels in data and info
-    let chan_len = data[0].len();
+    let chan_len = data.first().map_or(0, |channel| channel.len());
 
     for i in 0..data.len() {
         if data[i].len() != chan_len {return Err(Error::ChannelDataMalformed(chan_len, data[i].len()))} // data malformed, unequal sample length per channel
         // iterate through channels
-        let resolution = info[i].resolution;
-        if resolution.is_none() {continue} // dont need to scale if there is no scale available, should probably error or log this?
-        let resolution = f32::from(resolution.unwrap());
-        for j in 0..chan_len {
-            data[i][j] *= resolution; 
+        let resolution = T::from_f32(info[i].resolution);
+        for sample in data[i].iter_mut() {
+            *sample *= resolution; 
         }
     }
     Ok(())
 }
 
+/// Scales a window read from the data, `channels` are the indices of the window channels in `info`
+pub fn scale_window<T: Sample>(window: &mut [Vec<T>], channels: &[usize], info: &[ChannelInfo]) -> Result<(), Error> {
+    let window_info: Vec<ChannelInfo> = channels.iter()
+        .map(|channel| info.get(*channel).cloned().ok_or(Error::ChannelOutOfRange(*channel, info.len())))
+        .collect::<Result<_, _>>()?;
+    scale_channels(window, &window_info)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -34,11 +41,10 @@ mod tests {
         ],vec![
             80., 30., 80., 30.,
         ]];
-        let mut in_info = ChannelInfo::default();
-        in_info.resolution = Some(0.5);
+        let in_info = ChannelInfo{resolution: 0.5, ..Default::default()};
 
 
-        scale_channels(&mut in_data, &vec![in_info.clone(), in_info]).unwrap();
+        scale_channels(&mut in_data, &[in_info.clone(), in_info]).unwrap();
 
         let expected: Vec<Vec<f32>> = vec![vec![
             55., 10., 55., 10.,
@@ -49,4 +55,13 @@ mod tests {
         assert_eq!(in_data, expected)
 
     }
+
+    #[test]
+    fn test_scale_window() {
+        let mut window: Vec<Vec<f32>> = vec![vec![2., 4.], vec![2., 4.]];
+        let info = [ChannelInfo{resolution: 0.5, ..Default::default()}, ChannelInfo{resolution: 2., ..Default::default()}];
+        scale_window(&mut window, &[1, 0], &info).unwrap();
+        assert_eq!(window, vec![vec![4., 8.], vec![1., 2.]]);
+        assert_eq!(scale_window(&mut window, &[2], &info), Err(Error::ChannelOutOfRange(2, 2)));
+    }
 }
\ No newline at end of file
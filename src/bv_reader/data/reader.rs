@@ -0,0 +1,179 @@
+//!
+//! This module contains the random-access reader for binary data files
+//!
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::layout::DataLayout;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::data::parser::parse_bytestring::decode_sample;
+use crate::bv_reader::data::process::scale_channels::scale_window;
+use crate::bv_reader::header::parser::{
+    parse_binaryformat::BinaryFormat,
+    parse_dataorientation::DataOrientation,
+    parse_chan_info::ChannelInfo,
+};
+use crate::bv_reader::marker::parser::MarkerData;
+
+/// Reads windows of samples from any `Read + Seek` source without loading the whole data
+///
+/// Seeks directly to the byte offsets of the requested block: one contiguous read per window
+/// for MULTIPLEXED data, one per channel for VECTORIZED data.
+#[derive(Debug)]
+pub struct BVDataReader<R: Read + Seek> {
+    pub layout: DataLayout,
+    reader: R,
+}
+
+impl BVDataReader<BufReader<File>> {
+    pub fn from_file(datapath: &str, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        let file = File::open(datapath).map_err(|_| Error::FileOpen(datapath.to_string()))?;
+        BVDataReader::new(BufReader::new(file), num_channels, binary_format, orientation, use_big_endian)
+    }
+}
+
+impl<R: Read + Seek> BVDataReader<R> {
+    /// Derives the number of samples from the length of the source
+    pub fn new(mut reader: R, num_channels: usize, binary_format: BinaryFormat, orientation: DataOrientation, use_big_endian: bool) -> Result<Self, Error> {
+        let byte_len = reader.seek(SeekFrom::End(0)).map_err(|err| Error::DataRead(err.to_string()))?;
+        let layout = DataLayout::from_byte_len(byte_len as usize, num_channels, binary_format, orientation, use_big_endian)?;
+        Ok(BVDataReader{layout, reader})
+    }
+
+    pub fn num_channels(&self) -> usize {
+        self.layout.num_channels
+    }
+
+    /// Number of samples per channel
+    pub fn num_samples(&self) -> usize {
+        self.layout.num_samples
+    }
+
+    /// Returns the underlying source
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the raw values of the selected channels in the sample range, without scaling
+    ///
+    /// Returns one vector per requested channel in the requested order.
+    /// Returns Error::LossyConversion if `T` can not hold the binary format without loss of precision.
+    pub fn read_window<T: Sample>(&mut self, channels: &[usize], samples: Range<usize>) -> Result<Vec<Vec<T>>, Error> {
+        let layout = self.layout;
+        if !T::is_lossless_for(layout.binary_format) {
+            return Err(Error::LossyConversion(layout.binary_format.as_str().to_string(), T::NAME.to_string()))
+        }
+        layout.check_window(channels, &samples)?;
+
+        let bytes_per_sample = layout.bytes_per_sample();
+        let window_len = samples.len();
+        let decode = |chunk: &[u8]| decode_sample::<T>(chunk, layout.binary_format, layout.use_big_endian);
+
+        match layout.orientation {
+            DataOrientation::VECTORIZED => {
+                channels.iter()
+                    .map(|channel| {
+                        let block = self.read_block(layout.byte_offset(*channel, samples.start), window_len * bytes_per_sample)?;
+                        block.chunks_exact(bytes_per_sample).map(decode).collect()
+                    })
+                    .collect()
+            },
+            _ => {
+                // all channels of the window are stored contiguously
+                let block = self.read_block(layout.byte_offset(0, samples.start), window_len * layout.num_channels * bytes_per_sample)?;
+                channels.iter()
+                    .map(|channel| (0..window_len)
+                        .map(|sample| decode(&block[(sample * layout.num_channels + channel) * bytes_per_sample..]))
+                        .collect())
+                    .collect()
+            },
+        }
+    }
+
+    /// Same as [BVDataReader::read_window] but scales the channels according to their resolution
+    ///
+    /// `channel_info` holds all channels of the header, like [crate::bv_reader::BVMappedFile::read_window].
+    pub fn read_scaled_window<T: Sample>(&mut self, channels: &[usize], samples: Range<usize>, channel_info: &[ChannelInfo]) -> Result<Vec<Vec<T>>, Error> {
+        let mut window = self.read_window(channels, samples)?;
+        scale_window(&mut window, channels, channel_info)?;
+        Ok(window)
+    }
+
+    fn read_block(&mut self, offset: usize, len: usize) -> Result<Vec<u8>, Error> {
+        let mut block = vec![0; len];
+        self.reader.seek(SeekFrom::Start(offset as u64)).map_err(|err| Error::DataRead(err.to_string()))?;
+        self.reader.read_exact(&mut block).map_err(|err| Error::DataRead(err.to_string()))?;
+        Ok(block)
+    }
+}
+
+/// Returns the 0-based sample range from `pre` samples before to `post` samples after a marker, clamped at 0
+///
+/// Marker positions are 1-based, the marker sample itself is part of the range.
+pub fn marker_epoch(marker: &MarkerData, pre: usize, post: usize) -> Range<usize> {
+    let sample = marker.marker_position.saturating_sub(1);
+    sample.saturating_sub(pre)..sample + post + 1
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::bv_reader::data::BVData;
+
+    #[test]
+    fn test_read_window_multiplexed() {
+        let input = "src/bv_reader/data/testfiles/01_data.eeg";
+        let mut reader = BVDataReader::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let full = BVData::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        assert_eq!(reader.num_samples(), 100);
+
+        let output: Vec<Vec<f32>> = reader.read_window(&[70, 3], 10..20).unwrap();
+        assert_eq!(output, vec![full.data[70][10..20].to_vec(), full.data[3][10..20].to_vec()]);
+    }
+
+    #[test]
+    fn test_read_window_vectorized() {
+        // 2 channels with 3 samples each
+        let input: Vec<u8> = [1i16, 2, 3, -1, -2, -3].iter().flat_map(|x| x.to_be_bytes()).collect();
+        let mut reader = BVDataReader::new(Cursor::new(input), 2, BinaryFormat::INT_16, DataOrientation::VECTORIZED, true).unwrap();
+        let output: Vec<Vec<f32>> = reader.read_window(&[1, 0], 1..3).unwrap();
+        let expected = vec![vec![-2., -3.], vec![2., 3.]];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_read_window_out_of_range() {
+        let input: Vec<u8> = vec![0; 8];
+        let mut reader = BVDataReader::new(Cursor::new(input), 2, BinaryFormat::INT_16, DataOrientation::MULTIPLEXED, false).unwrap();
+        let output = reader.read_window::<f32>(&[0], 1..3);
+        let expected = Err(Error::SampleRangeOutOfRange(1, 3, 2));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_read_scaled_window() {
+        let input = "src/bv_reader/data/testfiles/01_data.eeg";
+        let mut reader = BVDataReader::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let mut full = BVData::from_file(input, 71, BinaryFormat::IEEE_FLOAT_32, DataOrientation::MULTIPLEXED, false).unwrap();
+        let header = crate::bv_reader::header::BVheader::from_file("src/bv_reader/data/testfiles/01_header.vhdr").unwrap();
+        full.scale_channels(&header.channel_info).unwrap();
+
+        let output: Vec<Vec<f32>> = reader.read_scaled_window(&[0, 64], 90..100, &header.channel_info).unwrap();
+        assert_eq!(output, vec![full.data[0][90..100].to_vec(), full.data[64][90..100].to_vec()]);
+        assert_eq!(reader.read_scaled_window::<f32>(&[0], 0..1, &[]), Err(Error::ChannelOutOfRange(0, 0)));
+    }
+
+    #[test]
+    fn test_marker_epoch() {
+        let input = MarkerData{marker_position: 8598, ..Default::default()};
+        assert_eq!(marker_epoch(&input, 10, 20), 8587..8618);
+        let input = MarkerData{marker_position: 1, ..Default::default()};
+        assert_eq!(marker_epoch(&input, 10, 0), 0..1);
+    }
+
+}
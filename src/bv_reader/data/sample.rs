@@ -0,0 +1,84 @@
+//!
+//! This module contains the `Sample` trait for the float types the data can be decoded to
+//!
+
+use std::fmt::Debug;
+use std::ops::MulAssign;
+
+use crate::bv_reader::header::parser::parse_binaryformat::BinaryFormat;
+
+/// Float type the binary samples are converted to
+///
+/// Implemented for `f32` and `f64`. `f32` can only hold the 16 bit formats and `IEEE_FLOAT_32` without
+/// losing precision, `f64` holds every format of the specification.
+pub trait Sample: Copy + Default + Debug + PartialEq + MulAssign {
+    /// Name of the type used in error messages
+    const NAME: &'static str;
+
+    fn from_i16(value: i16) -> Self;
+    fn from_u16(value: u16) -> Self;
+    fn from_i32(value: i32) -> Self;
+    fn from_u32(value: u32) -> Self;
+    fn from_f32(value: f32) -> Self;
+    fn from_f64(value: f64) -> Self;
+
+    /// Returns `sqrt(self² + other²)`
+    fn hypot(self, other: Self) -> Self;
+    /// Returns the angle of the point (other, self) in radians
+    fn atan2(self, other: Self) -> Self;
+
+    /// Returns true if every value of `format` can be represented exactly
+    fn is_lossless_for(format: BinaryFormat) -> bool;
+}
+
+impl Sample for f32 {
+    const NAME: &'static str = "f32";
+
+    fn from_i16(value: i16) -> Self {f32::from(value)}
+    fn from_u16(value: u16) -> Self {f32::from(value)}
+    fn from_i32(value: i32) -> Self {value as f32}
+    fn from_u32(value: u32) -> Self {value as f32}
+    fn from_f32(value: f32) -> Self {value}
+    fn from_f64(value: f64) -> Self {value as f32}
+
+    fn hypot(self, other: Self) -> Self {f32::hypot(self, other)}
+    fn atan2(self, other: Self) -> Self {f32::atan2(self, other)}
+
+    fn is_lossless_for(format: BinaryFormat) -> bool {
+        matches!(format, BinaryFormat::INT_16 | BinaryFormat::UINT_16 | BinaryFormat::IEEE_FLOAT_32)
+    }
+}
+
+impl Sample for f64 {
+    const NAME: &'static str = "f64";
+
+    fn from_i16(value: i16) -> Self {f64::from(value)}
+    fn from_u16(value: u16) -> Self {f64::from(value)}
+    fn from_i32(value: i32) -> Self {f64::from(value)}
+    fn from_u32(value: u32) -> Self {f64::from(value)}
+    fn from_f32(value: f32) -> Self {f64::from(value)}
+    fn from_f64(value: f64) -> Self {value}
+
+    fn hypot(self, other: Self) -> Self {f64::hypot(self, other)}
+    fn atan2(self, other: Self) -> Self {f64::atan2(self, other)}
+
+    fn is_lossless_for(format: BinaryFormat) -> bool {
+        format != BinaryFormat::Unknown
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_lossless_for() {
+        assert!(f32::is_lossless_for(BinaryFormat::INT_16));
+        assert!(!f32::is_lossless_for(BinaryFormat::INT_32));
+        assert!(!f32::is_lossless_for(BinaryFormat::IEEE_FLOAT_64));
+        assert!(f64::is_lossless_for(BinaryFormat::IEEE_FLOAT_64));
+        assert!(!f64::is_lossless_for(BinaryFormat::Unknown));
+    }
+
+}
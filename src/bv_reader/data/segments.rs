@@ -0,0 +1,65 @@
+//!
+//! This module contains the split of segmented data, e.g. epochs or averages exported by Analyzer
+//!
+
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::data::sample::Sample;
+use crate::bv_reader::marker::parser::MarkerData;
+
+/// One segment of all channels, borrowed from the continuous data
+#[derive(Debug, PartialEq, Clone)]
+pub struct Segment<'a, T: Sample = f32> {
+    pub index: usize,
+    /// "New Segment" marker at the first sample of the segment
+    pub marker: Option<&'a MarkerData>,
+    pub channels: Vec<&'a [T]>,
+}
+
+impl<T: Sample> Segment<'_, T> {
+    pub fn num_samples(&self) -> usize {
+        self.channels.first().map_or(0, |channel| channel.len())
+    }
+}
+
+/// Splits every channel into segments of `segment_len` samples without copying
+///
+/// Returns an error if the channels differ in length or are no multiple of `segment_len`
+pub fn split_segments<T: Sample>(data: &[Vec<T>], segment_len: usize) -> Result<Vec<Vec<&[T]>>, Error> {
+    let chan_len = data.first().map_or(0, |channel| channel.len());
+    if let Some(channel) = data.iter().find(|channel| channel.len() != chan_len) {
+        return Err(Error::ChannelDataMalformed(chan_len, channel.len()))
+    }
+    if segment_len == 0 || chan_len % segment_len != 0 {return Err(Error::SegmentMismatch(chan_len, segment_len))}
+
+    Ok((0..chan_len / segment_len)
+        .map(|segment| data.iter().map(|channel| &channel[segment * segment_len..(segment + 1) * segment_len]).collect())
+        .collect())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_segments() {
+        let input: Vec<Vec<f32>> = vec![vec![1., 2., 3., 4.], vec![5., 6., 7., 8.]];
+        let output = split_segments(&input, 2).unwrap();
+        let expected: Vec<Vec<&[f32]>> = vec![
+            vec![&[1., 2.], &[5., 6.]],
+            vec![&[3., 4.], &[7., 8.]],
+        ];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_split_segments_mismatch() {
+        let input: Vec<Vec<f32>> = vec![vec![1., 2., 3.]];
+        assert_eq!(split_segments(&input, 2), Err(Error::SegmentMismatch(3, 2)));
+        assert_eq!(split_segments(&input, 0), Err(Error::SegmentMismatch(3, 0)));
+
+        let input: Vec<Vec<f32>> = vec![vec![1., 2.], vec![3.]];
+        assert_eq!(split_segments(&input, 1), Err(Error::ChannelDataMalformed(2, 1)));
+    }
+
+}
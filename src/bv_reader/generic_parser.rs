@@ -3,6 +3,8 @@ use std::fmt::Debug;
 
 use regex::Regex;
 
+use crate::bv_reader::ini_parser::IniFile;
+
 /// Parses value with type T from string.
 /// ## Example
 /// ```
@@ -32,18 +34,60 @@ use regex::Regex;
 /// ```
 pub fn parse_generic_value<T: std::str::FromStr>(textcontent: &str, regex: &Regex) -> Option<T> where <T as FromStr>::Err: Debug {
     
-      let caps = regex.captures(textcontent);
-      if caps.is_some() {
-          let caps = caps.unwrap();
-           caps.get(1).map_or(Option::None, |m|  {
-              let numchan = m.as_str().parse::<T>();
-              if numchan.is_ok() {
-                  Some(numchan.unwrap())
-              } else {
-                  Option::None
-              }
-           })
-      } else {
-          Option::None
-      }
+      regex.captures(textcontent)
+          .and_then(|caps| caps.get(1))
+          .and_then(|m| m.as_str().parse::<T>().ok())
+}
+
+/// Parses value with type T from the entry `key` in `[section]`.
+/// ## Example
+/// ```
+///  use crate::bvreader::bv_reader::generic_parser::parse_generic_entry;
+///  use crate::bvreader::bv_reader::ini_parser::parse_ini;
+///
+///  let input = "[Common Infos]
+///  NumberOfChannels=71";
+///
+///  let output = parse_generic_entry::<usize>(&parse_ini(input), "Common Infos", "NumberOfChannels").unwrap();
+///  assert_eq!(output, 71);
+/// ```
+pub fn parse_generic_entry<T: std::str::FromStr>(ini: &IniFile, section: &str, key: &str) -> Option<T> {
+    ini.get_value(section, key)
+        .and_then(|value| value.parse::<T>().ok())
+}
+
+/// Escape sequence for commas inside of header and marker fields
+pub const ESCAPED_COMMA: &str = "\\1";
+
+/// Splits an entry value into its comma separated fields and decodes escaped commas ("\1") inside of each field.
+/// ## Example
+/// ```
+///  use crate::bvreader::bv_reader::generic_parser::split_fields;
+///
+///  let output = split_fields(r"Comment,Hello\1 World,1,1,0");
+///  assert_eq!(output, vec!["Comment", "Hello, World", "1", "1", "0"]);
+/// ```
+pub fn split_fields(value: &str) -> Vec<String> {
+    value.split(',')
+        .map(|field| field.replace(ESCAPED_COMMA, ","))
+        .collect()
+}
+
+/// Encodes commas inside of a field as "\1", so it can be written to a header or marker entry
+/// ## Example
+/// ```
+///  use crate::bvreader::bv_reader::generic_parser::escape_field;
+///
+///  assert_eq!(escape_field("Hello, World"), r"Hello\1 World");
+/// ```
+pub fn escape_field(field: &str) -> String {
+    field.replace(',', ESCAPED_COMMA)
+}
+
+/// Escapes and joins fields to an entry value, the inverse of [split_fields]
+pub fn join_fields<S: AsRef<str>>(fields: &[S]) -> String {
+    fields.iter()
+        .map(|field| escape_field(field.as_ref()))
+        .collect::<Vec<String>>()
+        .join(",")
 }
@@ -2,37 +2,56 @@
 use std::fs;
 //use std::fmt::Error;
 use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::ini_parser::parse_ini;
+use crate::bv_reader::montage::Montage;
 
 
 pub mod parser;
 
 // structs and enums
 use parser::{
+    parse_header_version::HeaderVersion,
     parse_header_encoding::HeaderEncoding, 
     parse_dataformat::DataFormat, 
     parse_dataorientation::DataOrientation,
     parse_binaryformat::BinaryFormat,
+    parse_ascii_infos::AsciiInfo,
     parse_chan_info::ChannelInfo,
-    //parse_chan_info_full::ChannelInfoFull,
+    parse_user_infos::UserProperty,
+    parse_chan_info_full::ChannelInfoFull,
+    parse_impedance::{Impedance, ChannelImpedance},
+    parse_software_filters::SoftwareFilters,
+    parse_amp_info::AmpInfo,
+    parse_datatype::DataType,
+    parse_layers::LayerInfo,
+    parse_segmentation::SegmentationInfo,
 };
 
 // functions
 use parser::{
     parse_header_version::parse_header_version,
-    parse_header_encoding::parse_header_encoding,
+    parse_header_encoding::{parse_header_encoding, decode_textcontent},
     parse_filepaths::{parse_datafilepath, parse_markerfilepath},
     parse_dataformat::parse_dataformat,
     parse_dataorientation::parse_data_orientation,
     parse_numchan::parse_numchans,
     parse_sinterval::parse_sampling_interval,
     parse_binaryformat::parse_binaryformat,
+    parse_ascii_infos::parse_ascii_infos,
     parse_chan_info::parse_chan_info,
     parse_recorder_version::parse_recorder_version,
     parse_amp_setup::{parse_amp_numchan, parse_amp_samplinginterval, parse_amp_samplingrate},
-    //parse_chan_info_full::parse_chan_info_full,
+    parse_chan_info_full::parse_chan_info_full,
     parse_reference::{parse_reference_label, parse_reference_phys_chan},
     parse_levels::{parse_good_level, parse_bad_level},
-    parse_endian::parse_endian
+    parse_impedance::parse_impedance,
+    parse_software_filters::parse_software_filters,
+    parse_amp_info::parse_amp_info,
+    parse_endian::parse_endian,
+    parse_user_infos::parse_user_infos,
+    parse_datatype::parse_datatype,
+    parse_layers::{parse_data_points, parse_layer_info},
+    parse_segmentation::parse_segmentation_info,
 };
 
 //pub mod validator;
@@ -45,7 +64,7 @@ use parser::{
 pub struct BVheader {
     pub header_path: String,
 
-    pub header_version: String,
+    pub header_version: HeaderVersion,
 
     // Common Info
     pub header_encoding: HeaderEncoding,
@@ -54,14 +73,27 @@ pub struct BVheader {
     pub data_format: DataFormat,
     pub data_orientation: DataOrientation,
     pub num_channels: usize,
-    pub sampling_interval: usize,
+    /// In microseconds, in Hz for frequency-domain data
+    pub sampling_interval: f64,
+    pub data_type: DataType,
+    /// Number of data points per channel and layer, not always present
+    pub data_points: Option<usize>,
+    /// Only present for data with layers, e.g. time-frequency data
+    pub layer_info: Option<LayerInfo>,
+    pub segmentation: SegmentationInfo,
 
     // Binary Format
     pub binary_format: BinaryFormat,
     pub use_big_endian: bool,
 
+    // ASCII Format
+    /// Only present for DataFormat::ASCII
+    pub ascii_info: Option<AsciiInfo>,
+
     // Channel Info
     pub channel_info: Vec<ChannelInfo>, 
+    /// Only present in version 2.0
+    pub channel_user_infos: Vec<UserProperty>,
 
     // Comment
     pub recorder_version: String,
@@ -72,63 +104,133 @@ pub struct BVheader {
     pub amp_sample_interval: usize, 
 
     // Channels Full
-    //pub channel_info_full: Vec<ChannelInfoFull>,
+    pub channel_info_full: Vec<ChannelInfoFull>,
 
     pub reference_label: String,
     pub reference_phys_chan: usize,
     //pub level_unit: String,
-    pub good_level: usize,
-    pub bad_level: usize, // done till here
+    /// None if the level is not given in the comment
+    pub good_level: Option<usize>,
+    /// None if the level is not given in the comment
+    pub bad_level: Option<usize>, // done till here
 
-    // not implemented, lacking example data
     //pub my_button_wd: String,
-    //pub amp_info: String,
+    pub amp_info: Option<AmpInfo>,
 
-    //pub software_filters: String,
+    pub software_filters: Option<SoftwareFilters>,
 
-    //pub impedence_values: String,
+    pub impedance_values: Option<Impedance>,
 
 }
 
 
 impl BVheader {
     pub fn from_file(filepath: &str) -> Result<Self, Error> {
-        let textcontent = fs::read_to_string(filepath);
-        if textcontent.is_err() {return Err(Error::FileRead(filepath.to_string()))};
-        let textcontent = textcontent.unwrap();
-        let header_version = parse_header_version(&textcontent);
-        if header_version.is_none() {return Err(Error::InvalidHeaderVersion)};
+        let bytestring = fs::read(filepath);
+        if bytestring.is_err() {return Err(Error::FileRead(filepath.to_string()))};
+        let mut bv_header = BVheader::from_bytes(&bytestring.unwrap())?;
+        bv_header.header_path = filepath.to_string();
+        Ok(bv_header)
+    }
+
+    /// Parses the raw content of a header file, decoded as in [BVheader::from_file]
+    pub fn from_bytes(bytestring: &[u8]) -> Result<Self, Error> {
+        BVheader::from_text(&decode_textcontent(bytestring))
+    }
+
+    /// Parses the decoded content of a header file, `header_path` stays empty
+    pub fn from_text(textcontent: &str) -> Result<Self, Error> {
+        let ini = parse_ini(textcontent);
+        let header_version = parse_header_version(&ini.version_line)?;
+        let comment = ini.comment();
 
         Ok(BVheader{
-            header_path: filepath.to_string(),
-            header_version: header_version.unwrap(),
-            header_encoding: parse_header_encoding(&textcontent),
-            data_file: parse_datafilepath(&textcontent).unwrap_or_default(),
-            marker_file: parse_markerfilepath(&textcontent).unwrap_or_default(),
-            data_format: parse_dataformat(&textcontent),
-            data_orientation: parse_data_orientation(&textcontent),
-            num_channels: parse_numchans(&textcontent).unwrap_or_default(),
-            sampling_interval: parse_sampling_interval(&textcontent).unwrap_or_default(),
-            binary_format: parse_binaryformat(&textcontent),
-            use_big_endian: parse_endian(&textcontent),
-            channel_info: parse_chan_info(&textcontent),
-            recorder_version: parse_recorder_version(&textcontent).unwrap_or_default(),
-            amp_channels: parse_amp_numchan(&textcontent).unwrap_or_default(),
-            amp_sr: parse_amp_samplingrate(&textcontent).unwrap_or_default(),
-            amp_sample_interval: parse_amp_samplinginterval(&textcontent).unwrap_or_default(),
-            //channel_info_full: parse_chan_info_full(&textcontent),
-            reference_label: parse_reference_label(&textcontent).unwrap_or_default(),
-            reference_phys_chan: parse_reference_phys_chan(&textcontent).unwrap_or_default(),
-            good_level: parse_good_level(&textcontent).unwrap_or_default(),
-            bad_level: parse_bad_level(&textcontent).unwrap_or_default(),
+            header_path: String::new(),
+            header_version,
+            header_encoding: parse_header_encoding(&ini),
+            data_file: parse_datafilepath(&ini).unwrap_or_default(),
+            marker_file: parse_markerfilepath(&ini).unwrap_or_default(),
+            data_format: parse_dataformat(&ini),
+            data_orientation: parse_data_orientation(&ini),
+            num_channels: parse_numchans(&ini).unwrap_or_default(),
+            sampling_interval: parse_sampling_interval(&ini).unwrap_or_default(),
+            data_type: parse_datatype(&ini),
+            data_points: parse_data_points(&ini),
+            layer_info: parse_layer_info(&ini),
+            segmentation: parse_segmentation_info(&ini),
+            binary_format: parse_binaryformat(&ini),
+            use_big_endian: parse_endian(&ini),
+            ascii_info: parse_ascii_infos(&ini),
+            channel_info: parse_chan_info(&ini)?,
+            channel_user_infos: parse_user_infos(&ini, "Channel User Infos"),
+            recorder_version: parse_recorder_version(comment).unwrap_or_default(),
+            amp_channels: parse_amp_numchan(comment).unwrap_or_default(),
+            amp_sr: parse_amp_samplingrate(comment).unwrap_or_default(),
+            amp_sample_interval: parse_amp_samplinginterval(comment).unwrap_or_default(),
+            channel_info_full: parse_chan_info_full(comment),
+            reference_label: parse_reference_label(comment).unwrap_or_default(),
+            reference_phys_chan: parse_reference_phys_chan(comment).unwrap_or_default(),
+            good_level: parse_good_level(comment),
+            bad_level: parse_bad_level(comment),
+            amp_info: parse_amp_info(comment),
+            software_filters: parse_software_filters(comment),
+            impedance_values: parse_impedance(comment),
         })    
     }
+
+    /// Returns the number of layers, 1 for data without layers
+    pub fn num_layers(&self) -> usize {
+        self.layer_info.map_or(1, |layer_info| layer_info.num_layers)
+    }
+
+    /// Returns the frequency of every data point in Hz
+    ///
+    /// Empty if the data is not in the frequency domain or `DataPoints` is not present.
+    pub fn frequency_bins(&self) -> Vec<f64> {
+        let Some(num_points) = self.data_points.filter(|_| self.data_type.is_frequency_domain()) else {return vec![]};
+        (0..num_points).map(|point| point as f64 * self.sampling_interval).collect()
+    }
+
+    /// Sets the approximate position of every channel without `[Coordinates]` entry from a standard montage
+    ///
+    /// Labels are matched case-insensitively. Returns the labels that are not part of the montage, e.g. auxiliary channels.
+    pub fn apply_montage(&mut self, montage: &Montage) -> Vec<String> {
+        let missing: Vec<&mut ChannelInfo> = self.channel_info.iter_mut().filter(|info| info.position.is_none()).collect();
+        let labels: Vec<&str> = missing.iter().map(|info| info.label.as_str()).collect();
+        let (positions, unmatched) = montage.match_labels(&labels);
+        for (info, position) in missing.into_iter().zip(positions) {
+            info.position = position;
+        }
+        unmatched
+    }
+
+    /// Returns the electrodes with an impedance above `good_level`
+    /// 
+    /// Empty if no impedance values or no good level are available
+    pub fn channels_above_good_level(&self) -> Vec<&ChannelImpedance> {
+        self.channels_above_level(self.good_level)
+    }
+
+    /// Returns the electrodes with an impedance above `bad_level`
+    /// 
+    /// Empty if no impedance values or no bad level are available
+    pub fn channels_above_bad_level(&self) -> Vec<&ChannelImpedance> {
+        self.channels_above_level(self.bad_level)
+    }
+
+    fn channels_above_level(&self, level: Option<usize>) -> Vec<&ChannelImpedance> {
+        match (&self.impedance_values, level) {
+            (Some(impedance), Some(level)) => impedance.channels_above(level as f32),
+            _ => vec![],
+        }
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use parser::parse_chan_info::DataUnit;
 
     #[test]
     fn test_parse_header() {
@@ -141,6 +243,118 @@ mod tests {
         assert_eq!(output.num_channels, expected);
     }
 
+    #[test]
+    fn test_parse_header_version_2() {
+        let input = "src/bv_reader/data/testfiles/02_header.vhdr";
+        let output = BVheader::from_file(input).unwrap();
+        let expected = HeaderVersion::V2_0;
+        assert_eq!(output.header_version, expected);
+    }
+
+    #[test]
+    fn test_parse_header_ansi() {
+        let input = "src/bv_reader/data/testfiles/03_header_ansi.vhdr";
+        let output = BVheader::from_file(input).unwrap();
+        assert_eq!(output.header_encoding, HeaderEncoding::ANSI);
+        assert_eq!(output.channel_info[63].label, "Ohrläppchen");
+        assert_eq!(output.channel_info[63].unit, DataUnit::uV);
+        assert_eq!(output.amp_sample_interval, 2000);
+    }
+
+    #[test]
+    fn test_parse_header_channel_info_full() {
+        let input = "src/bv_reader/data/testfiles/01_header.vhdr";
+        let output = BVheader::from_file(input).unwrap();
+        assert_eq!(output.channel_info_full.len(), 71);
+        assert_eq!(output.channel_info_full[23].label, "C4");
+        assert_eq!(output.channel_info_full[23].phys_chan, 25);
+        assert_eq!(output.channel_info_full[70].resolution, 0.298023);
+    }
+
+    #[test]
+    fn test_channels_above_level() {
+        let input = "src/bv_reader/data/testfiles/01_header.vhdr";
+        let mut output = BVheader::from_file(input).unwrap();
+        assert_eq!(output.impedance_values, Option::None);
+        assert!(output.channels_above_bad_level().is_empty());
+
+        output.impedance_values = parser::parse_impedance::parse_impedance("Impedance [kOhm] at 12:58:05 :
+        Fp1:          5
+        Fz:           25
+        F3:           60
+        Gnd:          1");
+        let good: Vec<&str> = output.channels_above_good_level().iter().map(|chan| chan.label.as_str()).collect();
+        let bad: Vec<&str> = output.channels_above_bad_level().iter().map(|chan| chan.label.as_str()).collect();
+        assert_eq!(good, vec!["Fz", "F3"]);
+        assert_eq!(bad, vec!["F3"]);
+
+        // a missing level does not default to 0 kOhm
+        output.good_level = None;
+        assert!(output.channels_above_good_level().is_empty());
+        assert_eq!(output.channels_above_bad_level().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_header_amp_info() {
+        let input = "src/bv_reader/data/testfiles/01_header.vhdr";
+        let output = BVheader::from_file(input).unwrap();
+        let amp_info = output.amp_info.unwrap();
+        assert_eq!(amp_info.model, "actiCHamp Base Unit");
+        assert_eq!(amp_info.serial, Some("17060821".to_string()));
+        assert_eq!(amp_info.modules.len(), 2);
+        assert_eq!(amp_info.firmware.len(), 6);
+        assert_eq!(output.software_filters, Some(SoftwareFilters::Disabled));
+    }
+
+    #[test]
+    fn test_parse_header_frequencydomain() {
+        let input = "Brain Vision Data Exchange Header File Version 1.0
+        [Common Infos]
+        DataFile=fft.dat
+        DataFormat=BINARY
+        DataOrientation=MULTIPLEXED
+        DataType=FREQUENCYDOMAIN
+        NumberOfChannels=1
+        DataPoints=4
+        SamplingInterval=0.5
+        [Binary Infos]
+        BinaryFormat=IEEE_FLOAT_32
+        [Channel Infos]
+        Ch1=Fz,,1,µV";
+        let output = BVheader::from_text(input).unwrap();
+        assert_eq!(output.data_type, DataType::Frequencydomain);
+        assert_eq!(output.data_points, Some(4));
+        assert_eq!(output.num_layers(), 1);
+        assert_eq!(output.frequency_bins(), vec![0., 0.5, 1., 1.5]);
+
+        let output = BVheader::from_file("src/bv_reader/data/testfiles/01_header.vhdr").unwrap();
+        assert_eq!(output.data_type, DataType::Timedomain);
+        assert_eq!(output.frequency_bins(), Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_apply_montage() {
+        let input = "src/bv_reader/data/testfiles/01_header.vhdr";
+        let mut output = BVheader::from_file(input).unwrap();
+        let unmatched = output.apply_montage(&Montage::ActiCap64);
+        let expected: Vec<String> = (65..=72).map(|label| label.to_string()).collect();
+        assert_eq!(unmatched, expected);
+        assert_eq!(output.channel_info[1].position, crate::bv_reader::montage::standard_position("Fz"));
+        assert_eq!(output.channel_info[62].position, crate::bv_reader::montage::standard_position("FCz"));
+        assert_eq!(output.channel_info[63].position, Option::None);
+
+        // positions from [Coordinates] are kept
+        let mut output = BVheader::from_text("Brain Vision Data Exchange Header File Version 1.0
+        [Channel Infos]
+        Ch1=cz,,1,µV
+        Ch2=Fz,,1,µV
+        [Coordinates]
+        Ch2=1,40,90").unwrap();
+        assert_eq!(output.apply_montage(&Montage::Standard1020), Vec::<String>::new());
+        assert_eq!(output.channel_info[0].position.unwrap().theta, 0.);
+        assert_eq!(output.channel_info[1].position.unwrap().theta, 40.);
+    }
+
     #[test]
     fn test_parse_header_empty() {
         let input = "";
@@ -6,15 +6,23 @@ pub mod parse_dataorientation;
 pub mod parse_numchan;
 pub mod parse_sinterval;
 pub mod parse_binaryformat;
+pub mod parse_ascii_infos;
 pub mod parse_chan_info;
 pub mod parse_recorder_version;
 pub mod parse_amp_setup;
 pub mod parse_chan_info_full;
 pub mod parse_reference;
 pub mod parse_levels;
+pub mod parse_impedance;
+pub mod parse_software_filters;
+pub mod parse_amp_info;
 
 // added options
 pub mod parse_datatype;
 pub mod parse_endian;
+pub mod parse_user_infos;
+pub mod parse_layers;
+pub mod parse_segmentation;
+pub mod parse_coordinates;
 
 //pub mod parse_value;
@@ -0,0 +1,151 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Extension module of the amplifier, e.g. `--- Module 1 (5010): S/N 17061547 ---`
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct AmpModule {
+    /// Name of the module group, e.g. "actiCHamp 32 CH Module"
+    pub name: String,
+    pub number: usize,
+    pub type_id: String,
+    pub serial: String,
+}
+
+/// One entry of the version line, e.g. `DLL_25.17.04.28`
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct FirmwareVersion {
+    pub component: String,
+    pub version: String,
+}
+
+/// Amplifier hardware information from the `[Comment]` section
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct AmpInfo {
+    pub model: String,
+    pub model_id: Option<String>,
+    pub serial: Option<String>,
+    pub modules: Vec<AmpModule>,
+    pub firmware: Vec<FirmwareVersion>,
+}
+
+static AMP_NAME_REGEX: OnceLock<Regex> = OnceLock::new();
+static AMP_SERIAL_REGEX: OnceLock<Regex> = OnceLock::new();
+static AMP_MODULE_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Returns None if no amplifier block is present
+///
+/// Expects the block written by the recorder:
+/// ```text
+/// Amplifier
+/// actiCHamp Base Unit (5001)
+///   --- S/N 17060821 ---
+///
+/// actiCHamp 32 CH Module
+///   --- Module 1 (5010): S/N 17061547 ---
+/// Version: DLL_25.17.04.28, DRV_03.04.01.146
+/// ```
+pub fn parse_amp_info(textcontent: &str) -> Option<AmpInfo> {
+
+    let name_re = AMP_NAME_REGEX.get_or_init(|| {
+        Regex::new(r"^(.*?)\s*\((\w+)\)$").unwrap()
+      });
+    let serial_re = AMP_SERIAL_REGEX.get_or_init(|| {
+        Regex::new(r"^--- S/N (\w+) ---$").unwrap()
+      });
+    let module_re = AMP_MODULE_REGEX.get_or_init(|| {
+        Regex::new(r"^--- Module (\d+) \((\w+)\): S/N (\w+) ---$").unwrap()
+      });
+
+    let mut lines = textcontent.lines().map(|line| line.trim());
+    lines.by_ref().find(|line| *line == "Amplifier")?;
+
+    let mut amp_info = AmpInfo::default();
+    let mut module_name = String::new();
+
+    for line in lines {
+        if line.is_empty() {continue}
+
+        if let Some(versions) = line.strip_prefix("Version:") {
+            amp_info.firmware = versions.split(',')
+                .map(|version| version.trim())
+                .filter(|version| !version.is_empty())
+                .map(|version| {
+                    let (component, version) = version.split_once('_').unwrap_or((version, ""));
+                    FirmwareVersion{component: component.to_string(), version: version.to_string()}
+                })
+                .collect();
+            break;
+        } else if let Some(caps) = module_re.captures(line) {
+            amp_info.modules.push(AmpModule{
+                name: module_name.clone(),
+                number: caps[1].parse().unwrap_or_default(),
+                type_id: caps[2].to_string(),
+                serial: caps[3].to_string(),
+            });
+        } else if let Some(caps) = serial_re.captures(line) {
+            amp_info.serial = Some(caps[1].to_string());
+        } else if line.starts_with("S o f t w a r e") || line.starts_with("Impedance") {
+            break;
+        } else if amp_info.model.is_empty() {
+            match name_re.captures(line) {
+                Some(caps) => {
+                    amp_info.model = caps[1].to_string();
+                    amp_info.model_id = Some(caps[2].to_string());
+                },
+                None => amp_info.model = line.to_string(),
+            }
+        } else {
+            module_name = line.to_string();
+        }
+    }
+
+    Some(amp_info)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amp_info() {
+        let input = "
+        MY-Button Workspace    = C:\\Vision\\Workfiles\\MYButton
+
+        Amplifier
+        actiCHamp Base Unit (5001)
+          --- S/N 17060821 ---
+
+        actiCHamp 32 CH Module
+          --- Module 1 (5010): S/N 17061547 ---
+          --- Module 2 (5010): S/N 17061548 ---
+        Version: DLL_25.17.04.28, DRV_03.04.01.146, CTRL_103.17.02.27
+
+
+        S o f t w a r e  F i l t e r s";
+        let output = parse_amp_info(input).unwrap();
+        assert_eq!(output.model, "actiCHamp Base Unit");
+        assert_eq!(output.model_id, Some("5001".to_string()));
+        assert_eq!(output.serial, Some("17060821".to_string()));
+        assert_eq!(output.modules.len(), 2);
+        assert_eq!(output.modules[1], AmpModule{
+            name: "actiCHamp 32 CH Module".to_string(),
+            number: 2,
+            type_id: "5010".to_string(),
+            serial: "17061548".to_string(),
+        });
+        assert_eq!(output.firmware.len(), 3);
+        assert_eq!(output.firmware[1], FirmwareVersion{component: "DRV".to_string(), version: "03.04.01.146".to_string()});
+    }
+
+    #[test]
+    fn test_parse_amp_info_empty() {
+        let input = "
+        Reference Channel Name = Cz
+        Reference Phys. Chn.   = 24";
+        let output = parse_amp_info(input);
+        let expected = Option::None;
+        assert_eq!(output, expected);
+    }
+
+}
@@ -0,0 +1,73 @@
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::parse_generic_entry;
+
+/// Layout of ASCII data files from `[ASCII Infos]`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsciiInfo {
+    /// Decimal symbol of the values, "." or ","
+    pub decimal_symbol: char,
+    /// Number of header lines before the data, e.g. channel names in multiplexed files
+    pub skip_lines: usize,
+    /// Number of leading columns in each line, e.g. channel names in vectorized files
+    pub skip_columns: usize,
+}
+
+impl Default for AsciiInfo {
+    fn default() -> Self {
+        AsciiInfo{
+            decimal_symbol: '.',
+            skip_lines: 0,
+            skip_columns: 0,
+        }
+    }
+}
+
+/// Returns Option::None if `[ASCII Infos]` is not present, omitted entries get their default values
+pub fn parse_ascii_infos(ini: &IniFile) -> Option<AsciiInfo> {
+    ini.section("ASCII Infos")?;
+
+    Some(AsciiInfo{
+        decimal_symbol: parse_generic_entry::<char>(ini, "ASCII Infos", "DecimalSymbol").unwrap_or('.'),
+        skip_lines: parse_generic_entry::<usize>(ini, "ASCII Infos", "SkipLines").unwrap_or_default(),
+        skip_columns: parse_generic_entry::<usize>(ini, "ASCII Infos", "SkipColumns").unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
+
+    #[test]
+    fn test_parse_ascii_infos() {
+        let input = "[Common Infos]
+        DataFormat=ASCII
+
+        [ASCII Infos]
+        DecimalSymbol=,
+        SkipLines=1
+        SkipColumns=0";
+        let output = parse_ascii_infos(&parse_ini(input));
+        let expected = Some(AsciiInfo{decimal_symbol: ',', skip_lines: 1, skip_columns: 0});
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_ascii_infos_defaults() {
+        let input = "[ASCII Infos]
+        SkipColumns=1";
+        let output = parse_ascii_infos(&parse_ini(input));
+        let expected = Some(AsciiInfo{decimal_symbol: '.', skip_lines: 0, skip_columns: 1});
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_ascii_infos_empty() {
+        let input = "[Binary Infos]
+        BinaryFormat=INT_16";
+        let output = parse_ascii_infos(&parse_ini(input));
+        let expected = Option::None;
+        assert_eq!(output, expected);
+    }
+
+}
@@ -1,5 +1,4 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
 
 #[allow(non_camel_case_types)]
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
@@ -8,37 +7,55 @@ pub enum BinaryFormat{
     IEEE_FLOAT_32,
     INT_16,
     UINT_16,
+    INT_32,
+    UINT_32,
+    IEEE_FLOAT_64,
     Unknown,
 }
 
-static BINFORMAT_REGEX: OnceLock<Regex> = OnceLock::new();
+impl BinaryFormat {
+    /// Returns the format as written in the header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BinaryFormat::IEEE_FLOAT_32 => "IEEE_FLOAT_32",
+            BinaryFormat::INT_16 => "INT_16",
+            BinaryFormat::UINT_16 => "UINT_16",
+            BinaryFormat::INT_32 => "INT_32",
+            BinaryFormat::UINT_32 => "UINT_32",
+            BinaryFormat::IEEE_FLOAT_64 => "IEEE_FLOAT_64",
+            BinaryFormat::Unknown => "Unknown",
+        }
+    }
+
+    /// Returns None for BinaryFormat::Unknown
+    pub fn bytes_per_sample(&self) -> Option<usize> {
+        match self {
+            BinaryFormat::INT_16 | BinaryFormat::UINT_16 => Some(2),
+            BinaryFormat::IEEE_FLOAT_32 | BinaryFormat::INT_32 | BinaryFormat::UINT_32 => Some(4),
+            BinaryFormat::IEEE_FLOAT_64 => Some(8),
+            BinaryFormat::Unknown => None,
+        }
+    }
+}
 
-/// Returns BinaryFormat::Unknown if no valid format was found
-pub fn parse_binaryformat(textcontent: &str) -> BinaryFormat {
+/// Returns BinaryFormat::Unknown if no valid format was found in `[Binary Infos]`
+pub fn parse_binaryformat(ini: &IniFile) -> BinaryFormat {
 
-    let re = BINFORMAT_REGEX.get_or_init(|| {
-        Regex::new(r"BinaryFormat=(\w*)").unwrap() 
-      });
-    
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(BinaryFormat::Unknown, |m| {
-            match m.as_str() { 
-                "IEEE_FLOAT_32" => BinaryFormat::IEEE_FLOAT_32,
-                "INT_16" => BinaryFormat::INT_16,
-                "UINT_16" => BinaryFormat::UINT_16,
-                _ => BinaryFormat::Unknown
-            }
-        })
-    } else {
-        BinaryFormat::Unknown
+    match ini.get_value("Binary Infos", "BinaryFormat") {
+        Some("IEEE_FLOAT_32") => BinaryFormat::IEEE_FLOAT_32,
+        Some("INT_16") => BinaryFormat::INT_16,
+        Some("UINT_16") => BinaryFormat::UINT_16,
+        Some("INT_32") => BinaryFormat::INT_32,
+        Some("UINT_32") => BinaryFormat::UINT_32,
+        Some("IEEE_FLOAT_64") => BinaryFormat::IEEE_FLOAT_64,
+        _ => BinaryFormat::Unknown
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_binaryformat() {
@@ -68,17 +85,27 @@ mod tests {
         Ch2=Fz,,0.0488281,µV
         Ch3=F3,,0.0488281,µV
         ";
-        let output = parse_binaryformat(input);
+        let output = parse_binaryformat(&parse_ini(input));
         let expected = BinaryFormat::IEEE_FLOAT_32;
         assert_eq!(output, expected);
     }
 
+    #[test]
+    fn test_parse_binaryformat_64() {
+        let input = "[Binary Infos]
+        BinaryFormat=IEEE_FLOAT_64";
+        let output = parse_binaryformat(&parse_ini(input));
+        let expected = BinaryFormat::IEEE_FLOAT_64;
+        assert_eq!(output, expected);
+        assert_eq!(output.bytes_per_sample(), Some(8));
+    }
+
     #[test]
     fn test_parse_binaryformat_empty() {
         let input = "[Common Infos]
         Codepage=
         DataFile=01_data.eeg";
-        let output = parse_binaryformat(input);
+        let output = parse_binaryformat(&parse_ini(input));
         let expected = BinaryFormat::Unknown;
         assert_eq!(output, expected);
     }
@@ -1,5 +1,7 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::{split_fields, join_fields};
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::header::parser::parse_coordinates::{SphericalPosition, parse_coordinates};
 
 /* _unit_dict = {
     "V": 1.0,  # V stands for Volt
@@ -32,102 +34,263 @@ pub enum DataUnit {
     uS, 
     N,
     NA,
+    /// Any other unit, kept as written in the header
+    Other(String),
 }
 
 
-#[derive(Default, Clone, Debug, PartialEq)]
+impl DataUnit {
+    /// Returns the unit as written in the header
+    pub fn as_str(&self) -> &str {
+        match self {
+            DataUnit::V => "V",
+            DataUnit::mV => "mV",
+            DataUnit::uV => "µV",
+            DataUnit::nV => "nV",
+            DataUnit::C => "°C",
+            DataUnit::ARU => "ARU",
+            DataUnit::S => "S",
+            DataUnit::uS => "µS",
+            DataUnit::N => "N",
+            DataUnit::NA => "n/a",
+            DataUnit::Other(unit) => unit.as_str(),
+        }
+    }
+}
+
+/// Returns DataUnit::Other for units that are not known
+pub fn parse_data_unit(unit: &str) -> DataUnit {
+    match unit {
+        "V" => DataUnit::V,
+        "mV" => DataUnit::mV,
+        "uV"|"µV" => DataUnit::uV,
+        "nV" => DataUnit::nV,
+        "C"|"°C" => DataUnit::C,
+        "S" => DataUnit::S,
+        "uS"|"µS" => DataUnit::uS,
+        "ARU" => DataUnit::ARU,
+        "N" => DataUnit::N,
+        "n/a" => DataUnit::NA,
+        x => DataUnit::Other(x.to_string()),
+    }
+}
+
+
+/// One entry of `[Channel Infos]`: Ch<Number>=<Name>,<Reference channel name>,<Resolution in "Unit">,<Unit>,<Future extensions..>
+#[derive(Clone, Debug, PartialEq)]
 pub struct ChannelInfo {
     pub header_id: String,
     pub label: String,
     pub reference: String,
-    pub resolution: Option<f32>,
+    /// Defaults to 1 if omitted
+    pub resolution: f32,
+    /// Defaults to µV if omitted
     pub unit: DataUnit,
+    /// Fields after the unit, e.g. written by newer recorder versions
+    pub extra_fields: Vec<String>,
+    /// From the `[Coordinates]` section, if present
+    pub position: Option<SphericalPosition>,
 }
 
+impl Default for ChannelInfo {
+    fn default() -> Self {
+        ChannelInfo{
+            header_id: String::new(),
+            label: String::new(),
+            reference: String::new(),
+            resolution: 1.,
+            unit: DataUnit::default(),
+            extra_fields: vec![],
+            position: None,
+        }
+    }
+}
 
+impl ChannelInfo {
+    /// Returns the `[Channel Infos]` entry of the channel, commas in label and reference are escaped as "\\1"
+    pub fn to_entry(&self) -> String {
+        let mut fields = vec![
+            self.label.clone(),
+            self.reference.clone(),
+            self.resolution.to_string(),
+            self.unit.as_str().to_string(),
+        ];
+        fields.extend(self.extra_fields.iter().cloned());
+        format!("{}={}", self.header_id, join_fields(&fields))
+    }
+}
 
-static CHANINFO_REGEX: OnceLock<Regex> = OnceLock::new();
 
-/// Returns empty Vec if no channelinfo present
-pub fn parse_chan_info(textcontent: &str) -> Vec<ChannelInfo> {
+/// Returns empty Vec if no channelinfo present in `[Channel Infos]`
+///
+/// Omitted reference, resolution and unit fields get their default values, fields after the unit are kept in `extra_fields`.
+/// Positions are taken from the `[Coordinates]` entry with the same key.
+/// Returns Error::InvalidChannelInfo for entries without a label or with a resolution that is not a number.
+pub fn parse_chan_info(ini: &IniFile) -> Result<Vec<ChannelInfo>, Error> {
 
-    let re = CHANINFO_REGEX.get_or_init(|| {
-        Regex::new(r"([Cch].\d.*)=([\w\.,]*)").unwrap() 
-      });
-    
     let mut results = vec![];
+    let Some(section) = ini.section("Channel Infos") else {return Ok(results)};
+    let mut positions = parse_coordinates(ini)?;
 
-    // Iterate over matches and extract components
-    for caps in re.captures_iter(textcontent) {
-        let header_id = caps.get(1).map(|m| m.as_str());
-        if header_id.is_some() { // serves as validation that we can safely unwrap everything in this line
-            let header_id = header_id.unwrap().to_string();
-            let second_capture = caps.get(2).map(|m| m.as_str()).unwrap();
-            let split_values: Vec<&str> = second_capture.split(',').collect();
-
-            if split_values.len() == 4 {
-                let label: String = split_values[0].to_string();
-                let reference: String = split_values[1].to_string();
-                let _res = split_values[2].parse::<f32>();
-                let resolution: Option<f32> = if _res.is_ok() {Some(_res.unwrap())} else {Option::None};
-                let unit = split_values[3];
-                let unit = match unit {
-                    "V" => DataUnit::V,
-                    "mV" => DataUnit::mV,
-                    "uV"|"µV" => DataUnit::uV,
-                    "nV" => DataUnit::nV,
-                    "C"|"°C" => DataUnit::C,
-                    "S" => DataUnit::S,
-                    "uS"|"µS" => DataUnit::uS,
-                    "ARU" => DataUnit::ARU,
-                    "N" => DataUnit::N,
-                    "n/a" => DataUnit::NA,
-                    _ => DataUnit::NA,
-                };
-
-    
-                let chan = ChannelInfo{
-                    header_id,
-                    label,
-                    reference,
-                    resolution,
-                    unit
-                };
-                results.push(chan);
-            }
-        }    
+    // Iterate over entries and extract components
+    for (header_id, value) in section.entries.iter().filter(|(key, _)| key.starts_with("Ch")) {
+        let mut split_values = split_fields(value).into_iter();
+        let invalid = || Error::InvalidChannelInfo(format!("{header_id}={value}"));
+
+        let label = split_values.next().filter(|label| !label.is_empty()).ok_or_else(invalid)?;
+        let reference = split_values.next().unwrap_or_default();
+        let resolution = match split_values.next().filter(|res| !res.is_empty()) {
+            Some(res) => res.parse::<f32>().map_err(|_| invalid())?,
+            None => 1.,
+        };
+        let unit = split_values.next()
+            .filter(|unit| !unit.is_empty())
+            .map_or(DataUnit::default(), |unit| parse_data_unit(&unit));
+
+        results.push(ChannelInfo{
+            header_id: header_id.to_string(),
+            label,
+            reference,
+            resolution,
+            unit,
+            extra_fields: split_values.collect(),
+            position: positions.remove(header_id),
+        });
     }
-    results
+    Ok(results)
 
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_chan_info() {
-        let input = "; Commas in channel names are coded as.
+        let input = "[Channel Infos]
+        ; Commas in channel names are coded as.
         Ch1=Fp1,,0.0488281,µV";
-        let output = parse_chan_info(input);
+        let output = parse_chan_info(&parse_ini(input)).unwrap();
         let expected = vec![ChannelInfo{
             header_id: "Ch1".to_string(),
             label: "Fp1".to_string(),
             reference: "".to_string(),
-            resolution: Some(0.0488281),
+            resolution: 0.0488281,
             unit: DataUnit::uV,
+            extra_fields: vec![],
+            position: None,
         }];
         assert_eq!(output, expected);
     }
 
+    #[test]
+    fn test_parse_chan_info_coordinates() {
+        let input = "[Channel Infos]
+        Ch1=Fp1,,0.5,µV
+        Ch2=AUX,,0.5,µV
+        [Coordinates]
+        Ch1=1,-90,-72
+        Ch2=0,0,0";
+        let output = parse_chan_info(&parse_ini(input)).unwrap();
+        assert_eq!(output[0].position, Some(SphericalPosition{radius: 1., theta: -90., phi: -72.}));
+        assert_eq!(output[1].position, Option::None);
+    }
+
     #[test]
     fn test_parse_chan_info_empty() {
         let input = "[Common Infos]
         Codepage=
         DataFile=01_data.eeg";
-        let output = parse_chan_info(input);
+        let output = parse_chan_info(&parse_ini(input)).unwrap();
+        let expected = vec![];
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_chan_info_wrong_section() {
+        let input = "[Common Infos]
+        Ch1=Fp1,,0.0488281,µV
+        [Comment]
+        Ch2=Fz,,0.0488281,µV";
+        let output = parse_chan_info(&parse_ini(input)).unwrap();
         let expected = vec![];
         assert_eq!(output, expected);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_parse_chan_info_escaped() {
+        let input = "[Channel Infos]
+        Ch1=EOG\\1 left/up,Fp-ref,0.5,µV
+        Ch2=Zäpfchen,,1,µV";
+        let output = parse_chan_info(&parse_ini(input)).unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].label, "EOG, left/up");
+        assert_eq!(output[0].reference, "Fp-ref");
+        assert_eq!(output[1].label, "Zäpfchen");
+    }
+
+    #[test]
+    fn test_chan_info_to_entry() {
+        let input = ChannelInfo{
+            header_id: "Ch1".to_string(),
+            label: "EOG, left".to_string(),
+            reference: "".to_string(),
+            resolution: 0.0488281,
+            unit: DataUnit::uV,
+            extra_fields: vec![],
+            position: None,
+        };
+        let output = input.to_entry();
+        let expected = "Ch1=EOG\\1 left,,0.0488281,µV";
+        assert_eq!(output, expected);
+        assert_eq!(parse_chan_info(&parse_ini(&format!("[Channel Infos]\n{output}"))).unwrap(), vec![input]);
+    }
+
+    #[test]
+    fn test_parse_chan_info_omitted_fields() {
+        let input = "[Channel Infos]
+        Ch1=Fp1,,0.1
+        Ch2=Fz
+        Ch3=Cz,,,
+        Ch4=Pz,,0.5,mV,foo,bar";
+        let output = parse_chan_info(&parse_ini(input)).unwrap();
+        assert_eq!(output.len(), 4);
+        assert_eq!(output[0].resolution, 0.1);
+        assert_eq!(output[0].unit, DataUnit::uV);
+        assert_eq!(output[1].resolution, 1.);
+        assert_eq!(output[2].resolution, 1.);
+        assert_eq!(output[2].unit, DataUnit::uV);
+        assert_eq!(output[3].unit, DataUnit::mV);
+        assert_eq!(output[3].extra_fields, vec!["foo".to_string(), "bar".to_string()]);
+        assert_eq!(output[3].to_entry(), "Ch4=Pz,,0.5,mV,foo,bar");
+    }
+
+    #[test]
+    fn test_parse_chan_info_unknown_unit() {
+        let input = "[Channel Infos]
+        Ch1=Fp1,,0.1,ms
+        Ch2=Fz,,1,n/a";
+        let output = parse_chan_info(&parse_ini(input)).unwrap();
+        assert_eq!(output[0].unit, DataUnit::Other("ms".to_string()));
+        assert_eq!(output[0].to_entry(), "Ch1=Fp1,,0.1,ms");
+        assert_eq!(output[1].unit, DataUnit::NA);
+        assert_eq!(output[1].to_entry(), "Ch2=Fz,,1,n/a");
+    }
+
+    #[test]
+    fn test_parse_chan_info_invalid() {
+        let input = "[Channel Infos]
+        Ch1=Fp1,,0.1,µV
+        Ch2=Fz,,abc,µV";
+        let output = parse_chan_info(&parse_ini(input));
+        let expected = Err(Error::InvalidChannelInfo("Ch2=Fz,,abc,µV".to_string()));
+        assert_eq!(output, expected);
+
+        let input = "[Channel Infos]
+        Ch1=,,0.1,µV";
+        let output = parse_chan_info(&parse_ini(input));
+        assert!(output.is_err());
+    }
+
+}
@@ -1,16 +1,15 @@
-use regex::Regex;
-use std::sync::OnceLock;
-
-
-#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub enum FilterSetting {
     #[default]
     OFF,
-    ON(usize),
+    ON(f32),
     DC,
     Unknown,
 }
 
+/// One row of the "Channels" table in the `[Comment]` section
+///
+/// The low cutoff is given as time constant in seconds, high cutoff and notch in Hz.
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct ChannelInfoFull {
     pub id: usize,
@@ -22,72 +21,93 @@ pub struct ChannelInfoFull {
     pub high_cutoff: FilterSetting,
     pub notch: FilterSetting,
     pub gradient: Option<f32>,
-    pub offset: Option<usize>,
+    pub offset: Option<f32>,
+}
+
+impl ChannelInfoFull {
+    /// Converts the low cutoff time constant to the cutoff frequency in Hz: f = 1 / (2 * pi * tau)
+    ///
+    /// Returns None if the low cutoff is not a time constant (DC, OFF, Unknown)
+    pub fn low_cutoff_hz(&self) -> Option<f32> {
+        match self.low_cutoff {
+            FilterSetting::ON(tau) if tau > 0. => Some(1. / (2. * std::f32::consts::PI * tau)),
+            _ => None,
+        }
+    }
 }
 
 
-static CHANINFOFULL_REGEX: OnceLock<Regex> = OnceLock::new();
-/// Returns empty Vec if no channelinfo present
+/// Returns empty Vec if no channel table is present
+///
+/// Expects the table layout written by the recorder:
+///
+/// `#     Name      Phys. Chn.    Resolution / Unit   Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]    Gradient         Offset`
+///
+/// Names are read from the `Name` column up to the `Phys. Chn.` column of the table header, so they may contain spaces.
+/// Rows that do not match the layout are skipped.
 pub fn parse_chan_info_full(textcontent: &str) -> Vec<ChannelInfoFull> {
 
-    let re = CHANINFOFULL_REGEX.get_or_init(|| {
-        Regex::new(r"(\d*)\W*(\w*)\W*(\d*)\W*(\d\.\d*)\W(\w{1,2})\W*(\w*)\W*(\w*)\W*(\w*)").unwrap() // this does not include options for gradient and offset!
-      });
-    
-    let mut results = vec![];
-
-    // Iterate over matches and extract components
-    for caps in re.captures_iter(textcontent) {
-        let id = caps.get(1).map(|m| m.as_str());
-        if id.is_some() { // serves as validation that we can safely unwrap everything in this line
-            let id = id.unwrap().parse::<usize>().unwrap();
-            let label = caps.get(2).map(|m| m.as_str().to_string()).unwrap();
-            let phys_chan = caps.get(3).map(|m| m.as_str().parse::<usize>().unwrap()).unwrap();
-            let resolution = caps.get(4).map(|m| m.as_str().parse::<f32>().unwrap()).unwrap();
-            let unit = caps.get(5).map(|m| m.as_str().to_string()).unwrap();
-            let low_cutoff = caps.get(6).map(|m| parse_filter_setting(m.as_str())).unwrap();
-            let high_cutoff = caps.get(7).map(|m| parse_filter_setting(m.as_str())).unwrap();
-            let notch = caps.get(8).map(|m| parse_filter_setting(m.as_str())).unwrap();
-            let gradient: Option<f32> = Option::None;
-            let offset: Option<usize> = Option::None;
-
-            let chan_info = ChannelInfoFull {
-                id,
-                label,
-                phys_chan,
-                resolution,
-                unit,
-                low_cutoff,
-                high_cutoff,
-                notch,
-                gradient,
-                offset,
-            };
-            results.push(chan_info);
-        }
-    }    
-    results
+    let mut lines = textcontent.lines()
+        .map(|line| line.trim())
+        .skip_while(|line| !(line.starts_with('#') && line.contains("Phys. Chn.")));
+    let Some(table_header) = lines.next() else {return vec![]};
+    let (Some(name_column), Some(phys_column)) = (char_column(table_header, "Name"), char_column(table_header, "Phys. Chn.")) else {return vec![]};
 
+    lines
+        .take_while(|line| !line.is_empty()) // rows end with the first empty line after the table header
+        .filter_map(|line| parse_chan_info_full_row(line, name_column, phys_column))
+        .collect()
 }
 
+/// Returns the character position of `title` in the table header
+fn char_column(table_header: &str, title: &str) -> Option<usize> {
+    table_header.find(title).map(|byte_pos| table_header[..byte_pos].chars().count())
+}
 
-static DIGIT_REGEX: OnceLock<Regex> = OnceLock::new();
+/// Splits the row at the character positions of the `Name` and `Phys. Chn.` columns
+///
+/// A name that is longer than its column ends at the next whitespace after the `Phys. Chn.` position.
+/// Returns None if the id crosses into the name column or the row does not contain at least the columns up to the notch filter.
+pub fn parse_chan_info_full_row(line: &str, name_column: usize, phys_column: usize) -> Option<ChannelInfoFull> {
+    let byte_pos = |column: usize| line.char_indices().nth(column).map_or(line.len(), |(pos, _)| pos);
+    let crosses_border = |left: &str, right: &str| left.ends_with(|c: char| !c.is_whitespace()) && right.starts_with(|c: char| !c.is_whitespace());
+
+    let (id, rest) = line.split_at(byte_pos(name_column));
+    if crosses_border(id, rest) {return None}
+    let mut label_len = byte_pos(phys_column) - id.len();
+    if crosses_border(&rest[..label_len], &rest[label_len..]) {
+        label_len += rest[label_len..].find(char::is_whitespace).unwrap_or(rest.len() - label_len);
+    }
+    let (label, rest) = rest.split_at(label_len);
+
+    let columns: Vec<&str> = rest.split_whitespace().collect();
+    if columns.len() < 6 {return None}
+
+    Some(ChannelInfoFull {
+        id: id.trim().parse::<usize>().ok()?,
+        label: label.trim().to_string(),
+        phys_chan: columns[0].parse::<usize>().ok()?,
+        resolution: columns[1].parse::<f32>().ok()?,
+        unit: columns[2].to_string(),
+        low_cutoff: parse_filter_setting(columns[3]),
+        high_cutoff: parse_filter_setting(columns[4]),
+        notch: parse_filter_setting(columns[5]),
+        gradient: columns.get(6).and_then(|x| x.parse::<f32>().ok()),
+        offset: columns.get(7).and_then(|x| x.parse::<f32>().ok()),
+    })
+}
 
-pub fn parse_filter_setting(input: &str) -> FilterSetting {
 
-    let digit_re = DIGIT_REGEX.get_or_init(|| {
-        Regex::new(r"(\d*)").unwrap() 
-      });
+/// Returns FilterSetting::Unknown if the input is neither a number nor one of DC / Off
+pub fn parse_filter_setting(input: &str) -> FilterSetting {
 
     match input {
         "DC" => {FilterSetting::DC},
         "OFF"|"Off" => {FilterSetting::OFF},
-        x => { 
-            if digit_re.is_match(x) {
-                let freq = x.parse::<usize>().expect(&format!("Error parsing FilterSetting! Input was = \" {} \" try into usize", x));
-                FilterSetting::ON(freq)
-            } else {
-                FilterSetting::Unknown
+        x => {
+            match x.parse::<f32>() {
+                Ok(freq) => FilterSetting::ON(freq),
+                Err(_) => FilterSetting::Unknown,
             }
         }
     }
@@ -106,16 +126,24 @@ mod tests {
         let output = parse_filter_setting(input);
         let expected = FilterSetting::DC;
         assert_eq!(output, expected);
-    }    
+    }
 
     #[test]
     fn test_parse_filter_setting_on() {
         // DC              140              Off
         let input = "140";
         let output = parse_filter_setting(input);
-        let expected = FilterSetting::ON(140);
+        let expected = FilterSetting::ON(140.);
         assert_eq!(output, expected);
-    } 
+    }
+
+    #[test]
+    fn test_parse_filter_setting_unknown() {
+        let input = "1 4O";
+        let output = parse_filter_setting(input);
+        let expected = FilterSetting::Unknown;
+        assert_eq!(output, expected);
+    }
 
 
     #[test]
@@ -135,7 +163,7 @@ mod tests {
             resolution: 0.0488281,
             unit: "µV".to_string(),
             low_cutoff: FilterSetting::DC,
-            high_cutoff: FilterSetting::ON(140),
+            high_cutoff: FilterSetting::ON(140.),
             notch: FilterSetting::OFF,
             gradient: Option::None,
             offset: Option::None,
@@ -143,6 +171,64 @@ mod tests {
         assert_eq!(output[0], expected);
     }
 
+    #[test]
+    fn test_parse_chan_info_full_gradient_offset() {
+        let input = "
+        #     Name      Phys. Chn.    Resolution / Unit   Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]    Gradient         Offset
+        1     GSR         1          1 µS                     10              1000             50           0.5              -2
+        2     Temp        2          0.1 °C                   0.0159          Off              Off
+
+        Reference Channel Name = Cz";
+        let output = parse_chan_info_full(input);
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].low_cutoff, FilterSetting::ON(10.));
+        assert_eq!(output[0].notch, FilterSetting::ON(50.));
+        assert_eq!(output[0].gradient, Some(0.5));
+        assert_eq!(output[0].offset, Some(-2.));
+        assert_eq!(output[1].unit, "°C");
+        assert_eq!(output[1].high_cutoff, FilterSetting::OFF);
+        assert_eq!(output[1].gradient, Option::None);
+    }
+
+    #[test]
+    fn test_parse_chan_info_full_malformed() {
+        let input = "
+        #     Name      Phys. Chn.    Resolution / Unit   Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]    Gradient         Offset
+        1     Fp1         x          0.0488281 µV             DC              140              Off
+        2     Fz          2          0.0488281 µV             DC              140
+        3     F3          3          0.0488281 µV             DC              140              Off";
+        let output = parse_chan_info_full(input);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].id, 3);
+    }
+
+    #[test]
+    fn test_parse_chan_info_full_name_with_spaces() {
+        let input = "
+        #     Name      Phys. Chn.    Resolution / Unit   Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]    Gradient         Offset
+        1     Left Ear    1          0.0488281 µV             DC              140              Off
+        2     Ohrläppchen 2          0.0488281 µV             DC              140              Off
+        3     Right Earlobe3         0.0488281 µV             DC              140              Off
+        4     A1 Ref      4          0.0488281 µV             DC              140              Off";
+        let output = parse_chan_info_full(input);
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0].label, "Left Ear");
+        assert_eq!(output[0].phys_chan, 1);
+        // longer than the name column
+        assert_eq!(output[1].label, "Ohrläppchen");
+        assert_eq!(output[1].phys_chan, 2);
+        assert_eq!(output[1].notch, FilterSetting::OFF);
+        // the name of row 3 runs into the channel number, the row is skipped
+        assert_eq!(output[2].label, "A1 Ref");
+    }
+
+    #[test]
+    fn test_low_cutoff_hz() {
+        let input = ChannelInfoFull{low_cutoff: FilterSetting::ON(10.), ..Default::default()};
+        let output = input.low_cutoff_hz().unwrap();
+        assert!((output - 0.015915).abs() < 1e-5);
+    }
+
     #[test]
     fn test_parse_chan_info_full_empty() {
         let input = "[Common Infos]
@@ -153,4 +239,4 @@ mod tests {
         assert_eq!(output, expected);
     }
 
-}
\ No newline at end of file
+}
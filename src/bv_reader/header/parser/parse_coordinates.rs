@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::split_fields;
+use crate::bv_reader::bv_error::Error;
+
+/// Electrode position on a sphere, one entry of `[Coordinates]`: Ch<Number>=<Radius>,<Theta>,<Phi>
+///
+/// Angles are in degrees. Theta is the angle from the vertex (Cz), negative on the left hemisphere,
+/// phi the angle in the horizontal plane counterclockwise from the right ear axis.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct SphericalPosition {
+    pub radius: f64,
+    pub theta: f64,
+    pub phi: f64,
+}
+
+/// Position in 3D with x towards the right ear, y towards the nose and z towards the vertex
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct CartesianPosition {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// Position on a topographic map, the equator of the sphere (theta = ±90°) lies on the unit circle
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct TopoPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl SphericalPosition {
+    /// Returns `(r sinθ cosφ, r sinθ sinφ, r cosθ)`
+    pub fn to_cartesian(&self) -> CartesianPosition {
+        let (theta, phi) = (self.theta.to_radians(), self.phi.to_radians());
+        CartesianPosition{
+            x: self.radius * theta.sin() * phi.cos(),
+            y: self.radius * theta.sin() * phi.sin(),
+            z: self.radius * theta.cos(),
+        }
+    }
+
+    /// Returns the azimuthal equidistant projection used for topographic maps, independent of the radius
+    pub fn to_topo(&self) -> TopoPosition {
+        let (distance, phi) = (self.theta / 90., self.phi.to_radians());
+        TopoPosition{
+            x: distance * phi.cos(),
+            y: distance * phi.sin(),
+        }
+    }
+
+    /// Returns the `[Coordinates]` value of the position
+    pub fn to_entry(&self) -> String {
+        format!("{},{},{}", self.radius, self.theta, self.phi)
+    }
+}
+
+/// Returns the positions by channel key, e.g. "Ch1", empty if `[Coordinates]` is not present
+///
+/// Channels written as `0,0,0` have no known position and are left out.
+/// Returns Error::InvalidCoordinates for entries that are not three numbers.
+pub fn parse_coordinates(ini: &IniFile) -> Result<HashMap<String, SphericalPosition>, Error> {
+
+    let mut results = HashMap::new();
+    let Some(section) = ini.section("Coordinates") else {return Ok(results)};
+
+    for (header_id, value) in section.entries.iter().filter(|(key, _)| key.starts_with("Ch")) {
+        let invalid = || Error::InvalidCoordinates(format!("{header_id}={value}"));
+        let values = split_fields(value).iter()
+            .map(|field| field.trim().parse::<f64>())
+            .collect::<Result<Vec<f64>, _>>()
+            .map_err(|_| invalid())?;
+        let [radius, theta, phi] = values[..] else {return Err(invalid())};
+        if radius == 0. && theta == 0. && phi == 0. {continue}
+
+        results.insert(header_id.to_string(), SphericalPosition{radius, theta, phi});
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
+
+    fn assert_close(output: (f64, f64, f64), expected: (f64, f64, f64)) {
+        for (a, b) in [(output.0, expected.0), (output.1, expected.1), (output.2, expected.2)] {
+            assert!((a - b).abs() < 1e-9, "{output:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_coordinates() {
+        let input = "[Coordinates]
+        Ch1=1,-90,-72
+        Ch2=1,45,90
+        Ch3=0,0,0";
+        let output = parse_coordinates(&parse_ini(input)).unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(output["Ch1"], SphericalPosition{radius: 1., theta: -90., phi: -72.});
+        assert_eq!(output["Ch2"].to_entry(), "1,45,90");
+        assert!(!output.contains_key("Ch3"));
+    }
+
+    #[test]
+    fn test_parse_coordinates_invalid() {
+        let input = "[Coordinates]
+        Ch1=1,90";
+        let output = parse_coordinates(&parse_ini(input));
+        let expected = Err(Error::InvalidCoordinates("Ch1=1,90".to_string()));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_coordinates_empty() {
+        let input = "[Channel Infos]
+        Ch1=Fp1,,0.5,µV";
+        let output = parse_coordinates(&parse_ini(input)).unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_to_cartesian() {
+        // Cz, T8 and Fpz
+        let output = SphericalPosition{radius: 1., theta: 0., phi: 0.}.to_cartesian();
+        assert_close((output.x, output.y, output.z), (0., 0., 1.));
+        let output = SphericalPosition{radius: 2., theta: 90., phi: 0.}.to_cartesian();
+        assert_close((output.x, output.y, output.z), (2., 0., 0.));
+        let output = SphericalPosition{radius: 1., theta: 90., phi: 90.}.to_cartesian();
+        assert_close((output.x, output.y, output.z), (0., 1., 0.));
+        // T7 via negative theta
+        let output = SphericalPosition{radius: 1., theta: -90., phi: 0.}.to_cartesian();
+        assert_close((output.x, output.y, output.z), (-1., 0., 0.));
+    }
+
+    #[test]
+    fn test_to_topo() {
+        let output = SphericalPosition{radius: 1., theta: 45., phi: 90.}.to_topo();
+        assert_close((output.x, output.y, 0.), (0., 0.5, 0.));
+        let output = SphericalPosition{radius: 5., theta: -90., phi: 0.}.to_topo();
+        assert_close((output.x, output.y, 0.), (-1., 0., 0.));
+    }
+
+}
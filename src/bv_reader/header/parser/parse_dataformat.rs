@@ -1,42 +1,31 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
 
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DataFormat {
     #[default]
     BINARY,
+    ASCII,
     Unknown,
 }
 
-static DATAFORMAT_REGEX: OnceLock<Regex> = OnceLock::new();
+/// Returns DataFormat::BINARY if `DataFormat` is not present in `[Common Infos]`, the default of the specification
+///
+/// Returns DataFormat::Unknown for any other value than BINARY or ASCII.
+pub fn parse_dataformat(ini: &IniFile) -> DataFormat {
 
-/// Returns DataFormat::Unknown if no valid format was found
-pub fn parse_dataformat(textcontent: &str) -> DataFormat {
-
-    let re = DATAFORMAT_REGEX.get_or_init(|| {
-        Regex::new(r"DataFormat=(\w*)").unwrap() 
-      });
-    
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(DataFormat::Unknown, |m| {
-            if m.as_str() == "BINARY" {
-                DataFormat::BINARY
-            } else {
-
-                DataFormat::Unknown
-            }
-        })
-    } else {
-        DataFormat::Unknown
+    match ini.get_value("Common Infos", "DataFormat") {
+        None => DataFormat::BINARY,
+        Some("BINARY") => DataFormat::BINARY,
+        Some("ASCII") => DataFormat::ASCII,
+        Some(_) => DataFormat::Unknown,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_dataformat() {
@@ -50,17 +39,35 @@ mod tests {
         NumberOfChannels=71
         ; Sampling interval in microseconds
         SamplingInterval=2000";
-        let output = parse_dataformat(input);
+        let output = parse_dataformat(&parse_ini(input));
         let expected = DataFormat::BINARY;
         assert_eq!(output, expected);
     }
 
+    #[test]
+    fn test_parse_dataformat_ascii() {
+        let input = "[Common Infos]
+        DataFormat=ASCII";
+        let output = parse_dataformat(&parse_ini(input));
+        let expected = DataFormat::ASCII;
+        assert_eq!(output, expected);
+    }
+
     #[test]
     fn test_parse_dataformat_empty() {
         let input = "[Common Infos]
         Codepage=
         DataFile=01_data.eeg";
-        let output = parse_dataformat(input);
+        let output = parse_dataformat(&parse_ini(input));
+        let expected = DataFormat::BINARY;
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_dataformat_unknown() {
+        let input = "[Common Infos]
+        DataFormat=BINARY_COMPRESSED";
+        let output = parse_dataformat(&parse_ini(input));
         let expected = DataFormat::Unknown;
         assert_eq!(output, expected);
     }
@@ -1,5 +1,4 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
 
 
 
@@ -11,33 +10,20 @@ pub enum DataOrientation{
     Unknown,
 }
 
-static DATAORIENTATION_REGEX: OnceLock<Regex> = OnceLock::new();
+/// Returns DataOrientation::Unknown if no valid orientation was found in `[Common Infos]`
+pub fn parse_data_orientation(ini: &IniFile) -> DataOrientation {
 
-/// Returns DataOrientation::Unknown if no valid orientation was found
-pub fn parse_data_orientation(textcontent: &str) -> DataOrientation {
-
-    let re = DATAORIENTATION_REGEX.get_or_init(|| {
-        Regex::new(r"DataOrientation=(\w*)").unwrap() 
-      });
-    
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(DataOrientation::Unknown, |m| {
-            match m.as_str() {
-                "MULTIPLEXED" => DataOrientation::MULTIPLEXED,
-                "VECTORIZED" => DataOrientation::VECTORIZED,
-                _ => DataOrientation::Unknown,
-            }
-        })
-    } else {
-        DataOrientation::Unknown
+    match ini.get_value("Common Infos", "DataOrientation") {
+        Some("MULTIPLEXED") => DataOrientation::MULTIPLEXED,
+        Some("VECTORIZED") => DataOrientation::VECTORIZED,
+        _ => DataOrientation::Unknown,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_dataorientation() {
@@ -51,7 +37,7 @@ mod tests {
         NumberOfChannels=71
         ; Sampling interval in microseconds
         SamplingInterval=2000";
-        let output = parse_data_orientation(input);
+        let output = parse_data_orientation(&parse_ini(input));
         let expected = DataOrientation::MULTIPLEXED;
         assert_eq!(output, expected);
     }
@@ -61,7 +47,7 @@ mod tests {
         let input = "[Common Infos]
         Codepage=
         DataFile=01_data.eeg";
-        let output = parse_data_orientation(input);
+        let output = parse_data_orientation(&parse_ini(input));
         let expected = DataOrientation::Unknown;
         assert_eq!(output, expected);
     }
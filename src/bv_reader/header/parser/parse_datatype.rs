@@ -1,5 +1,4 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
 
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
@@ -7,36 +6,39 @@ pub enum DataType {
     #[default]
     Timedomain,
     Frequencydomain,
+    /// Real and imaginary part per value
+    TimedomainComplex,
+    /// Real and imaginary part per value
+    FrequencydomainComplex,
     Unknown,
 }
 
-static DATAFORMAT_REGEX: OnceLock<Regex> = OnceLock::new();
+impl DataType {
+    pub fn is_complex(&self) -> bool {
+        matches!(self, DataType::TimedomainComplex | DataType::FrequencydomainComplex)
+    }
 
-/// Returns DataType::Timedomain if no DataType was specified
-pub fn parse_datatype(textcontent: &str) -> DataType {
+    pub fn is_frequency_domain(&self) -> bool {
+        matches!(self, DataType::Frequencydomain | DataType::FrequencydomainComplex)
+    }
+}
 
-    let re = DATAFORMAT_REGEX.get_or_init(|| {
-        Regex::new(r"DataType=(\w*)").unwrap() 
-      });
-    
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(DataType::Timedomain, |m| {
-            match m.as_str() {
-                "TIMEDOMAIN" => {DataType::Timedomain},
-                "FREQUENCYDOMAIN" => {DataType::Frequencydomain},
-                _ => {DataType::Unknown},
-            }
-        })
-    } else {
-        DataType::Timedomain
+/// Returns DataType::Timedomain if no DataType was specified in `[Common Infos]`
+pub fn parse_datatype(ini: &IniFile) -> DataType {
+
+    match ini.get_value("Common Infos", "DataType") {
+        Some("TIMEDOMAIN") | None => {DataType::Timedomain},
+        Some("FREQUENCYDOMAIN") => {DataType::Frequencydomain},
+        Some("TIMEDOMAIN_COMPLEX") => {DataType::TimedomainComplex},
+        Some("FREQUENCYDOMAIN_COMPLEX") => {DataType::FrequencydomainComplex},
+        Some(_) => {DataType::Unknown},
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_datatype() {
@@ -51,17 +53,28 @@ mod tests {
         ; Sampling interval in microseconds
         SamplingInterval=2000
         DataType=TIMEDOMAIN";
-        let output = parse_datatype(input);
+        let output = parse_datatype(&parse_ini(input));
         let expected = DataType::Timedomain;
         assert_eq!(output, expected);
     }
 
+    #[test]
+    fn test_parse_datatype_complex() {
+        let input = "[Common Infos]
+        DataType=FREQUENCYDOMAIN_COMPLEX";
+        let output = parse_datatype(&parse_ini(input));
+        let expected = DataType::FrequencydomainComplex;
+        assert_eq!(output, expected);
+        assert!(output.is_complex() && output.is_frequency_domain());
+        assert!(!DataType::Frequencydomain.is_complex());
+    }
+
     #[test]
     fn test_parse_datatype_empty() {
         let input = "[Common Infos]
         Codepage=
         DataFile=01_data.eeg";
-        let output = parse_datatype(input);
+        let output = parse_datatype(&parse_ini(input));
         let expected = DataType::Timedomain;
         assert_eq!(output, expected);
     }
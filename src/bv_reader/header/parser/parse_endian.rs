@@ -1,34 +1,16 @@
-use regex::Regex;
-use std::sync::OnceLock;
-
-
-static DATAFORMAT_REGEX: OnceLock<Regex> = OnceLock::new();
-
-/// Returns false if endian was not specified -> use little endian if false
-pub fn parse_endian(textcontent: &str) -> bool {
-
-    let re = DATAFORMAT_REGEX.get_or_init(|| {
-        Regex::new(r"UseBigEndianOrder=(\w*)").unwrap() 
-      });
-    
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(false, |m| {
-            match m.as_str() {
-                "YES" => {true},
-                "NO" => {false},
-                _ => {false},
-            }
-        })
-    } else {
-        false
-    }
+use crate::bv_reader::ini_parser::IniFile;
+
+
+/// Returns false if endian was not specified in `[Binary Infos]` -> use little endian if false
+pub fn parse_endian(ini: &IniFile) -> bool {
+
+    matches!(ini.get_value("Binary Infos", "UseBigEndianOrder"), Some("YES"))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_endian() {
@@ -42,9 +24,12 @@ mod tests {
         NumberOfChannels=71
         ; Sampling interval in microseconds
         SamplingInterval=2000
+
+        [Binary Infos]
+        BinaryFormat=INT_16
         UseBigEndianOrder=YES";
 
-        let output = parse_endian(input);
+        let output = parse_endian(&parse_ini(input));
         let expected = true;
         assert_eq!(output, expected);
     }
@@ -54,7 +39,7 @@ mod tests {
         let input = "[Common Infos]
         Codepage=
         DataFile=01_data.eeg";
-        let output = parse_endian(input);
+        let output = parse_endian(&parse_ini(input));
         let expected = false;
         assert_eq!(output, expected);
     }
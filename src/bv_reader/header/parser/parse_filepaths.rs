@@ -1,46 +1,33 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
 
-static DATAFILE_REGEX: OnceLock<Regex> = OnceLock::new();
-/// Returns the datafile path or Option::None
-pub fn parse_datafilepath(textcontent: &str) -> Option<String> {
-    let re = DATAFILE_REGEX.get_or_init(|| {
-        Regex::new(r"DataFile=([\w\.-]*)").unwrap() 
-      }); 
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(Option::None, |m|  Some(m.as_str().to_string()) )
-    } else {
-        Option::None
-    }
+/// Returns the whole value with surrounding whitespace trimmed, Option::None if empty
+fn parse_filepath_value(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {return None}
+    Some(value.to_string())
 }
 
-static MARKERFILE_REGEX: OnceLock<Regex> = OnceLock::new();
-/// Returns the markerfile path or Option::None
-pub fn parse_markerfilepath(textcontent: &str) -> Option<String> {
-    let re = MARKERFILE_REGEX.get_or_init(|| {
-        Regex::new(r"MarkerFile=([\w\.-]*)").unwrap() 
-      }); 
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(Option::None, |m|  Some(m.as_str().to_string()) )
-    } else {
-        Option::None
-    }
+/// Returns the datafile path from `[Common Infos]` or Option::None
+pub fn parse_datafilepath(ini: &IniFile) -> Option<String> {
+    ini.get_value("Common Infos", "DataFile").and_then(parse_filepath_value)
+}
+
+/// Returns the markerfile path from `[Common Infos]` or Option::None
+pub fn parse_markerfilepath(ini: &IniFile) -> Option<String> {
+    ini.get_value("Common Infos", "MarkerFile").and_then(parse_filepath_value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_datafilepath() {
         let input = "[Common Infos]
         Codepage=UTF-8
         DataFile=01_data.eeg";
-        let output = parse_datafilepath(input).unwrap();
+        let output = parse_datafilepath(&parse_ini(input)).unwrap();
         let expected = String::from("01_data.eeg");
         assert_eq!(output, expected);
     }
@@ -57,7 +44,7 @@ mod tests {
         NumberOfChannels=71
         ; Sampling interval in microseconds
         SamplingInterval=2000";
-        let output = parse_markerfilepath(input).unwrap();
+        let output = parse_markerfilepath(&parse_ini(input)).unwrap();
         let expected = String::from("01_marker.vmrk");
         assert_eq!(output, expected);
     }
@@ -67,9 +54,28 @@ mod tests {
         let input = "[Common Infos]
         Codepage=
         DataFil";
-        let output = parse_datafilepath(input);
+        let output = parse_datafilepath(&parse_ini(input));
         let expected = Option::None;
         assert_eq!(output, expected);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_parse_datafilepath_spaces() {
+        let input = "[Common Infos]
+        DataFile=  Subject 01 (rest) Ä.eeg  
+        MarkerFile=C:\\Recordings\\Subject 01 (rest).vmrk";
+        let ini = parse_ini(input);
+        assert_eq!(parse_datafilepath(&ini), Some("Subject 01 (rest) Ä.eeg".to_string()));
+        assert_eq!(parse_markerfilepath(&ini), Some("C:\\Recordings\\Subject 01 (rest).vmrk".to_string()));
+    }
+
+    #[test]
+    fn test_parse_datafilepath_blank() {
+        let input = "[Common Infos]
+        DataFile=   ";
+        let output = parse_datafilepath(&parse_ini(input));
+        let expected = Option::None;
+        assert_eq!(output, expected);
+    }
+
+}
@@ -1,5 +1,4 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum HeaderEncoding {
@@ -9,36 +8,79 @@ pub enum HeaderEncoding {
     Unknown,
 }
 
-static HEADER_ENCODING_REGEX: OnceLock<Regex> = OnceLock::new();
-
-/// Returns HeaderEncoding::Unknown if no valid encoding was found
-pub fn parse_header_encoding(textcontent: &str) -> HeaderEncoding {
-
-    let re = HEADER_ENCODING_REGEX.get_or_init(|| {
-        Regex::new(r"Codepage=([\w-]*)").unwrap() 
-      });
-    
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(HeaderEncoding::Unknown, |m| {
-            if m.as_str() == "UTF-8" {
-                HeaderEncoding::UTF8
-            } else if m.as_str() == "ANSI" {
-                HeaderEncoding::ANSI
-            } else {
-                HeaderEncoding::Unknown
+/// Returns HeaderEncoding::Unknown if no valid encoding was found in `[Common Infos]`
+pub fn parse_header_encoding(ini: &IniFile) -> HeaderEncoding {
+
+    match ini.get_value("Common Infos", "Codepage") {
+        Some(codepage) => parse_codepage(codepage),
+        None => HeaderEncoding::Unknown,
+    }
+}
+
+fn parse_codepage(codepage: &str) -> HeaderEncoding {
+    match codepage {
+        "UTF-8" => HeaderEncoding::UTF8,
+        "ANSI" => HeaderEncoding::ANSI,
+        _ => HeaderEncoding::Unknown,
+    }
+}
+
+/// Finds the `Codepage=` value in the raw bytes of a header or marker file
+/// 
+/// Returns HeaderEncoding::Unknown if no codepage was specified
+pub fn detect_header_encoding(bytestring: &[u8]) -> HeaderEncoding {
+    const KEY: &[u8] = b"Codepage=";
+
+    bytestring.split(|byte| *byte == b'\n')
+        .map(|line| line.trim_ascii())
+        .find_map(|line| line.strip_prefix(KEY))
+        .and_then(|value| std::str::from_utf8(value).ok())
+        .map_or(HeaderEncoding::Unknown, |value| parse_codepage(value.trim()))
+}
+
+/// Transcodes the raw bytes of a header or marker file to UTF-8
+/// 
+/// ANSI files are decoded as Windows-1252 (a superset of Latin-1).
+/// Files without a codepage or with an invalid UTF-8 body fall back to Windows-1252 as well,
+/// since older recorder versions wrote ANSI without declaring it.
+pub fn decode_textcontent(bytestring: &[u8]) -> String {
+    if let Some(utf8_body) = bytestring.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(utf8_body).into_owned();
+    }
+
+    match detect_header_encoding(bytestring) {
+        HeaderEncoding::ANSI => decode_windows_1252(bytestring),
+        HeaderEncoding::UTF8 | HeaderEncoding::Unknown => {
+            match std::str::from_utf8(bytestring) {
+                Ok(textcontent) => textcontent.to_string(),
+                Err(_) => decode_windows_1252(bytestring),
             }
-        })
-    } else {
-        HeaderEncoding::Unknown
+        },
     }
 }
 
+/// Characters of Windows-1252 in the range 0x80..=0x9F, all other bytes map to the same code point as in Latin-1
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+pub fn decode_windows_1252(bytestring: &[u8]) -> String {
+    bytestring.iter()
+        .map(|byte| match byte {
+            0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+            x => char::from(*x),
+        })
+        .collect()
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_header_encoding() {
@@ -71,7 +113,7 @@ mod tests {
         Ch7=FC1,,0.0488281,µV
         Ch8=C3,,0.0488281,µV
         Ch9=T7,,0.0488281,µV";
-        let output = parse_header_encoding(input);
+        let output = parse_header_encoding(&parse_ini(input));
         let expected = HeaderEncoding::UTF8;
         assert_eq!(output, expected);
     }
@@ -81,9 +123,41 @@ mod tests {
         let input = "[Common Infos]
         Codepage=
         DataFile=sadasdffs";
-        let output = parse_header_encoding(input);
+        let output = parse_header_encoding(&parse_ini(input));
         let expected = HeaderEncoding::Unknown;
         assert_eq!(output, expected);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_detect_header_encoding() {
+        let input = b"[Common Infos]\r\nCodepage=ANSI\r\nDataFile=01_data.eeg";
+        let output = detect_header_encoding(input);
+        let expected = HeaderEncoding::ANSI;
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_decode_textcontent_ansi() {
+        let input = b"Codepage=ANSI\nCh1=M\xFCnster,,0.1,\xB5V \x80";
+        let output = decode_textcontent(input);
+        let expected = "Codepage=ANSI\nCh1=Münster,,0.1,µV €";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_decode_textcontent_utf8() {
+        let input = "\u{feff}Codepage=UTF-8\nCh1=Fp1,,0.1,µV";
+        let output = decode_textcontent(input.as_bytes());
+        let expected = "Codepage=UTF-8\nCh1=Fp1,,0.1,µV";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_decode_textcontent_undeclared_ansi() {
+        let input = b"Ch1=Fp1,,0.1,\xB5V";
+        let output = decode_textcontent(input);
+        let expected = "Ch1=Fp1,,0.1,µV";
+        assert_eq!(output, expected);
+    }
+
+}
@@ -1,22 +1,48 @@
 use regex::Regex;
 use std::sync::OnceLock;
 
+use crate::bv_reader::bv_error::Error;
+
+/// Versions of the BrainVision Core Data Format that are understood by the reader
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeaderVersion {
+    #[default]
+    V1_0,
+    V2_0,
+}
+
+impl HeaderVersion {
+    /// Returns the version as written in the file, e.g. "1.0"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeaderVersion::V1_0 => "1.0",
+            HeaderVersion::V2_0 => "2.0",
+        }
+    }
+
+    /// Returns Error::UnsupportedHeaderVersion for versions that are not known
+    pub fn from_version_str(version: &str) -> Result<Self, Error> {
+        match version {
+            "1.0" => Ok(HeaderVersion::V1_0),
+            "2.0" => Ok(HeaderVersion::V2_0),
+            x => Err(Error::UnsupportedHeaderVersion(x.to_string())),
+        }
+    }
+}
+
 static HEADER_VERSION_REGEX: OnceLock<Regex> = OnceLock::new();
 
-/// Returns an empty String if no version was found
-pub fn parse_header_version(textcontent: &str) -> Option<String> {
+/// Returns Error::InvalidHeaderVersion if no version was found and Error::UnsupportedHeaderVersion if the version is not known
+pub fn parse_header_version(textcontent: &str) -> Result<HeaderVersion, Error> {
 
     let re = HEADER_VERSION_REGEX.get_or_init(|| {
         Regex::new(r"Brain Vision Data Exchange Header File Version (\d{1,}\.\d{1,})").unwrap() 
       });
     
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(Option::None, |m| Some(m.as_str().to_string()))
-    } else {
-        Option::None
-    }
+    let version = re.captures(textcontent)
+        .and_then(|caps| caps.get(1))
+        .ok_or(Error::InvalidHeaderVersion)?;
+    HeaderVersion::from_version_str(version.as_str())
 }
 
 #[cfg(test)]
@@ -27,7 +53,23 @@ mod tests {
     fn test_parse_header_version() {
         let input = "Brain Vision Data Exchange Header File Version 1.0";
         let output = parse_header_version(input).unwrap();
-        let expected = "1.0".to_string();
+        let expected = HeaderVersion::V1_0;
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_header_version_2() {
+        let input = "Brain Vision Data Exchange Header File Version 2.0";
+        let output = parse_header_version(input).unwrap();
+        let expected = HeaderVersion::V2_0;
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_header_version_unsupported() {
+        let input = "Brain Vision Data Exchange Header File Version 3.1";
+        let output = parse_header_version(input);
+        let expected = Err(Error::UnsupportedHeaderVersion("3.1".to_string()));
         assert_eq!(output, expected);
     }
 
@@ -35,8 +77,8 @@ mod tests {
     fn test_parse_header_version_empty() {
         let input = "Brain Vision Data Exchange Header File Version";
         let output = parse_header_version(input);
-        let expected = Option::None;
+        let expected = Err(Error::InvalidHeaderVersion);
         assert_eq!(output, expected);
     }
 
-}
\ No newline at end of file
+}
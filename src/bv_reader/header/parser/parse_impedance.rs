@@ -0,0 +1,174 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Value of a single electrode in the impedance table
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum ImpedanceValue {
+    Value(f32),
+    /// Written as "Out of Range!" by the recorder
+    OutOfRange,
+    /// Anything else, e.g. "???" or "Disconnected!"
+    #[default]
+    Unknown,
+}
+
+/// Time of day of the impedance measurement as written in the header (HH:MM:SS)
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImpedanceTime {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ChannelImpedance {
+    pub label: String,
+    pub value: ImpedanceValue,
+}
+
+impl ChannelImpedance {
+    /// Returns true if the impedance is above `level` or out of range
+    ///
+    /// Unknown values are never above the level
+    pub fn is_above(&self, level: f32) -> bool {
+        match self.value {
+            ImpedanceValue::Value(x) => x > level,
+            ImpedanceValue::OutOfRange => true,
+            ImpedanceValue::Unknown => false,
+        }
+    }
+}
+
+/// Impedance table from the `[Comment]` section
+///
+/// Ground and reference electrodes ("Gnd" / "Ref") are stored separately from the channels.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Impedance {
+    pub unit: String,
+    pub measurement_time: Option<ImpedanceTime>,
+    pub channels: Vec<ChannelImpedance>,
+    pub ground: Option<ChannelImpedance>,
+    pub reference: Option<ChannelImpedance>,
+}
+
+impl Impedance {
+    /// Returns all electrodes (including ground and reference) with an impedance above `level` or out of range
+    pub fn channels_above(&self, level: f32) -> Vec<&ChannelImpedance> {
+        self.channels.iter()
+            .chain(self.ground.iter())
+            .chain(self.reference.iter())
+            .filter(|chan| chan.is_above(level))
+            .collect()
+    }
+}
+
+static IMPEDANCE_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Returns None if no impedance values are available
+///
+/// Expects the table written by the recorder:
+/// ```text
+/// Impedance [kOhm] at 12:58:05 :
+/// Fp1:          5
+/// Fz:           Out of Range!
+/// Ref:          2
+/// Gnd:          1
+/// ```
+pub fn parse_impedance(textcontent: &str) -> Option<Impedance> {
+
+    let re = IMPEDANCE_REGEX.get_or_init(|| {
+        Regex::new(r"^Impedance \[(.*)\] at (\d{1,2}):(\d{2}):(\d{2})").unwrap()
+      });
+
+    let mut lines = textcontent.lines().map(|line| line.trim());
+    let caps = lines.by_ref().find_map(|line| re.captures(line))?;
+
+    let mut impedance = Impedance{
+        unit: caps.get(1).map_or("", |m| m.as_str()).to_string(),
+        measurement_time: parse_impedance_time(caps.get(2), caps.get(3), caps.get(4)),
+        ..Default::default()
+    };
+
+    for line in lines.take_while(|line| !line.is_empty()) {
+        let Some((label, value)) = line.split_once(':') else {continue};
+        let chan = ChannelImpedance{
+            label: label.trim().to_string(),
+            value: parse_impedance_value(value.trim()),
+        };
+        match chan.label.as_str() {
+            "Gnd" | "GND" | "Ground" => impedance.ground = Some(chan),
+            "Ref" | "REF" | "Reference" => impedance.reference = Some(chan),
+            _ => impedance.channels.push(chan),
+        }
+    }
+    Some(impedance)
+}
+
+fn parse_impedance_time(hour: Option<regex::Match>, minute: Option<regex::Match>, second: Option<regex::Match>) -> Option<ImpedanceTime> {
+    Some(ImpedanceTime{
+        hour: hour?.as_str().parse().ok()?,
+        minute: minute?.as_str().parse().ok()?,
+        second: second?.as_str().parse().ok()?,
+    })
+}
+
+pub fn parse_impedance_value(input: &str) -> ImpedanceValue {
+    match input {
+        "Out of Range!" => ImpedanceValue::OutOfRange,
+        x => x.parse::<f32>().map_or(ImpedanceValue::Unknown, ImpedanceValue::Value),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_impedance() {
+        let input = "
+        S o f t w a r e  F i l t e r s
+        ==============================
+        Disabled
+
+
+        Impedance [kOhm] at 16:24:28 :
+        Fp1:          5
+        Fz:           Out of Range!
+        F3:           ???
+        Ref:          2
+        Gnd:          1
+        ";
+        let output = parse_impedance(input).unwrap();
+        assert_eq!(output.unit, "kOhm");
+        assert_eq!(output.measurement_time, Some(ImpedanceTime{hour: 16, minute: 24, second: 28}));
+        assert_eq!(output.channels.len(), 3);
+        assert_eq!(output.channels[0], ChannelImpedance{label: "Fp1".to_string(), value: ImpedanceValue::Value(5.)});
+        assert_eq!(output.channels[1].value, ImpedanceValue::OutOfRange);
+        assert_eq!(output.channels[2].value, ImpedanceValue::Unknown);
+        assert_eq!(output.reference.unwrap().value, ImpedanceValue::Value(2.));
+        assert_eq!(output.ground.unwrap().value, ImpedanceValue::Value(1.));
+    }
+
+    #[test]
+    fn test_channels_above() {
+        let input = "Impedance [kOhm] at 16:24:28 :
+        Fp1:          5
+        Fz:           Out of Range!
+        F3:           12
+        Ref:          20
+        Gnd:          1";
+        let output = parse_impedance(input).unwrap();
+        let labels: Vec<&str> = output.channels_above(10.).iter().map(|chan| chan.label.as_str()).collect();
+        assert_eq!(labels, vec!["Fz", "F3", "Ref"]);
+    }
+
+    #[test]
+    fn test_parse_impedance_empty() {
+        let input = "No impedance values available at 12:58:05!";
+        let output = parse_impedance(input);
+        let expected = Option::None;
+        assert_eq!(output, expected);
+    }
+
+}
@@ -0,0 +1,105 @@
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::parse_generic_entry;
+
+/// Spacing of the layer frequencies between `LayerLowerLimit` and `LayerUpperLimit`
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerFunction {
+    #[default]
+    Linear,
+    Logarithmic,
+    Unknown,
+}
+
+/// Layers of time-frequency data, e.g. wavelet output exported by Analyzer
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct LayerInfo {
+    pub num_layers: usize,
+    /// Frequency of the first layer in Hz
+    pub lower_limit: Option<f64>,
+    /// Frequency of the last layer in Hz
+    pub upper_limit: Option<f64>,
+    pub function: LayerFunction,
+}
+
+impl LayerInfo {
+    /// Returns the frequency of every layer in Hz, empty if the limits are not present
+    pub fn frequencies(&self) -> Vec<f64> {
+        let (Some(lower), Some(upper)) = (self.lower_limit, self.upper_limit) else {return vec![]};
+        if self.num_layers < 2 {return vec![lower; self.num_layers]}
+
+        let steps = (self.num_layers - 1) as f64;
+        (0..self.num_layers).map(|layer| {
+            let fraction = layer as f64 / steps;
+            match self.function {
+                LayerFunction::Logarithmic => lower * (upper / lower).powf(fraction),
+                _ => lower + (upper - lower) * fraction,
+            }
+        }).collect()
+    }
+}
+
+/// Returns the number of data points per channel and layer or Option::None
+pub fn parse_data_points(ini: &IniFile) -> Option<usize> {
+    parse_generic_entry::<usize>(ini, "Common Infos", "DataPoints")
+}
+
+/// Returns Option::None if no `Layers` entry is present in `[Common Infos]`
+pub fn parse_layer_info(ini: &IniFile) -> Option<LayerInfo> {
+    let num_layers = parse_generic_entry::<usize>(ini, "Common Infos", "Layers")?;
+
+    Some(LayerInfo{
+        num_layers,
+        lower_limit: parse_generic_entry::<f64>(ini, "Common Infos", "LayerLowerLimit"),
+        upper_limit: parse_generic_entry::<f64>(ini, "Common Infos", "LayerUpperLimit"),
+        function: match ini.get_value("Common Infos", "LayerFunction") {
+            Some("LINEAR") | None => LayerFunction::Linear,
+            Some("LOGARITHMIC") => LayerFunction::Logarithmic,
+            Some(_) => LayerFunction::Unknown,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
+
+    #[test]
+    fn test_parse_layer_info() {
+        let input = "[Common Infos]
+        DataType=TIMEDOMAIN
+        DataPoints=512
+        Layers=3
+        LayerLowerLimit=1
+        LayerUpperLimit=4
+        LayerFunction=LOGARITHMIC";
+        let ini = parse_ini(input);
+        let output = parse_layer_info(&ini).unwrap();
+        let expected = LayerInfo{num_layers: 3, lower_limit: Some(1.), upper_limit: Some(4.), function: LayerFunction::Logarithmic};
+        assert_eq!(output, expected);
+        assert_eq!(output.frequencies(), vec![1., 2., 4.]);
+        assert_eq!(parse_data_points(&ini), Some(512));
+    }
+
+    #[test]
+    fn test_layer_frequencies_linear() {
+        let input = LayerInfo{num_layers: 3, lower_limit: Some(2.), upper_limit: Some(6.), ..Default::default()};
+        let output = input.frequencies();
+        let expected = vec![2., 4., 6.];
+        assert_eq!(output, expected);
+
+        let input = LayerInfo{num_layers: 3, ..Default::default()};
+        assert_eq!(input.frequencies(), Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_parse_layer_info_empty() {
+        let input = "[Common Infos]
+        DataPoints=
+        NumberOfChannels=71";
+        let ini = parse_ini(input);
+        assert_eq!(parse_layer_info(&ini), Option::None);
+        assert_eq!(parse_data_points(&ini), Option::None);
+    }
+
+}
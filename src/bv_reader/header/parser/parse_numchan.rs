@@ -1,21 +1,16 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::generic_parser::parse_generic_entry;
+use crate::bv_reader::ini_parser::IniFile;
 
-use crate::bv_reader::generic_parser::parse_generic_value;
-
-static NUMCHAN_REGEX: OnceLock<Regex> = OnceLock::new();
 /// Returns the number of channels or Option::None
-pub fn parse_numchans(textcontent: &str) -> Option<usize> {
-    let re = NUMCHAN_REGEX.get_or_init(|| {
-        Regex::new(r"NumberOfChannels=(\d*)").unwrap() 
-      }); 
-    parse_generic_value::<usize>(textcontent, re)
+pub fn parse_numchans(ini: &IniFile) -> Option<usize> {
+    parse_generic_entry::<usize>(ini, "Common Infos", "NumberOfChannels")
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_numchans() {
@@ -29,7 +24,7 @@ mod tests {
         NumberOfChannels=71
         ; Sampling interval in microseconds
         SamplingInterval=2000";
-        let output = parse_numchans(input).unwrap();
+        let output = parse_numchans(&parse_ini(input)).unwrap();
         let expected = 71;
         assert_eq!(output, expected);
     }
@@ -39,7 +34,7 @@ mod tests {
         let input = "[Common Infos]
         Codepage=
         DataFil";
-        let output = parse_numchans(input);
+        let output = parse_numchans(&parse_ini(input));
         let expected = Option::None;
         assert_eq!(output, expected);
     }
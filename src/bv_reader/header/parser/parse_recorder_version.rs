@@ -10,13 +10,9 @@ pub fn parse_recorder_version(textcontent: &str) -> Option<String> {
         Regex::new(r"BrainVision Recorder Professional   -   V. (.*)").unwrap() 
       });
     
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(Option::None, |m| Some(m.as_str().to_string()))
-    } else {
-        Option::None
-    }
+    re.captures(textcontent)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
 }
 
 #[cfg(test)]
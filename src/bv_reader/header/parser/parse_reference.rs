@@ -9,13 +9,9 @@ pub fn parse_reference_label(textcontent: &str) -> Option<String> {
     let re = REF_LABEL_REGEX.get_or_init(|| {
         Regex::new(r"Reference Channel Name = (\w*)").unwrap() 
       }); 
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(Option::None, |m|  Some(m.as_str().to_string()) )
-    } else {
-        Option::None
-    }
+    re.captures(textcontent)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
 }
 
 static REF_PHYS_REGEX: OnceLock<Regex> = OnceLock::new();
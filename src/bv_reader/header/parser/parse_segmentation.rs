@@ -0,0 +1,95 @@
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::parse_generic_entry;
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentationType {
+    #[default]
+    NotSegmented,
+    MarkerBased,
+    FixTime,
+    Manual,
+    Unknown,
+}
+
+/// Segmentation and averaging of data exported by Analyzer
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SegmentationInfo {
+    pub segmentation_type: SegmentationType,
+    /// Number of data points per segment
+    pub segment_data_points: Option<usize>,
+    pub averaged: bool,
+    /// Number of segments that went into the average
+    pub averaged_segments: Option<usize>,
+}
+
+impl SegmentationInfo {
+    /// True for every segmentation type except NotSegmented
+    ///
+    /// An unknown type still names a segmentation, so its data is split into `segment_data_points` like the known types.
+    pub fn is_segmented(&self) -> bool {
+        self.segmentation_type != SegmentationType::NotSegmented
+    }
+}
+
+/// Returns SegmentationType::NotSegmented and no averaging if the entries are not present in `[Common Infos]`
+pub fn parse_segmentation_info(ini: &IniFile) -> SegmentationInfo {
+    SegmentationInfo{
+        segmentation_type: match ini.get_value("Common Infos", "SegmentationType") {
+            Some("NOTSEGMENTED") | Some("") | None => SegmentationType::NotSegmented,
+            Some("MARKERBASED") => SegmentationType::MarkerBased,
+            Some("FIXTIME") => SegmentationType::FixTime,
+            Some("MANUAL") => SegmentationType::Manual,
+            Some(_) => SegmentationType::Unknown,
+        },
+        segment_data_points: parse_generic_entry::<usize>(ini, "Common Infos", "SegmentDataPoints"),
+        averaged: ini.get_value("Common Infos", "Averaged") == Some("YES"),
+        averaged_segments: parse_generic_entry::<usize>(ini, "Common Infos", "AveragedSegments"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
+
+    #[test]
+    fn test_parse_segmentation_info() {
+        let input = "[Common Infos]
+        DataType=TIMEDOMAIN
+        SegmentationType=MARKERBASED
+        SegmentDataPoints=500
+        Averaged=YES
+        AveragedSegments=42";
+        let output = parse_segmentation_info(&parse_ini(input));
+        let expected = SegmentationInfo{
+            segmentation_type: SegmentationType::MarkerBased,
+            segment_data_points: Some(500),
+            averaged: true,
+            averaged_segments: Some(42),
+        };
+        assert_eq!(output, expected);
+        assert!(output.is_segmented());
+    }
+
+    #[test]
+    fn test_parse_segmentation_info_empty() {
+        let input = "[Common Infos]
+        SegmentationType=
+        Averaged=NO";
+        let output = parse_segmentation_info(&parse_ini(input));
+        let expected = SegmentationInfo::default();
+        assert_eq!(output, expected);
+        assert!(!output.is_segmented());
+    }
+
+    #[test]
+    fn test_parse_segmentation_info_unknown() {
+        let input = "[Common Infos]
+        SegmentationType=AUTOMATIC
+        SegmentDataPoints=100";
+        let output = parse_segmentation_info(&parse_ini(input));
+        assert_eq!(output.segmentation_type, SegmentationType::Unknown);
+        assert!(output.is_segmented());
+    }
+
+}
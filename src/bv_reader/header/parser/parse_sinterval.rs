@@ -1,21 +1,18 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::generic_parser::parse_generic_entry;
+use crate::bv_reader::ini_parser::IniFile;
 
-use crate::bv_reader::generic_parser::parse_generic_value;
-
-static SINTER_REGEX: OnceLock<Regex> = OnceLock::new();
-/// Returns the sampling interval or Option::None
-pub fn parse_sampling_interval(textcontent: &str) -> Option<usize> {
-    let re = SINTER_REGEX.get_or_init(|| {
-        Regex::new(r"SamplingInterval=(\d*)").unwrap() 
-      }); 
-    parse_generic_value::<usize>(textcontent, re)
+/// Returns the sampling interval in microseconds or Option::None
+///
+/// The interval is not necessarily an integer, e.g. `3906.25` for 256 Hz
+pub fn parse_sampling_interval(ini: &IniFile) -> Option<f64> {
+    parse_generic_entry::<f64>(ini, "Common Infos", "SamplingInterval")
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_sampling_interval() {
@@ -29,8 +26,17 @@ mod tests {
         NumberOfChannels=71
         ; Sampling interval in microseconds
         SamplingInterval=2000";
-        let output = parse_sampling_interval(input).unwrap();
-        let expected = 2000;
+        let output = parse_sampling_interval(&parse_ini(input)).unwrap();
+        let expected = 2000.;
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_sampling_interval_fraction() {
+        let input = "[Common Infos]
+        SamplingInterval=3906.25";
+        let output = parse_sampling_interval(&parse_ini(input)).unwrap();
+        let expected = 3906.25;
         assert_eq!(output, expected);
     }
 
@@ -39,7 +45,7 @@ mod tests {
         let input = "[Common Infos]
         Codepage=
         DataFil";
-        let output = parse_sampling_interval(input);
+        let output = parse_sampling_interval(&parse_ini(input));
         let expected = Option::None;
         assert_eq!(output, expected);
     }
@@ -0,0 +1,135 @@
+use crate::bv_reader::header::parser::parse_chan_info_full::{FilterSetting, parse_filter_setting};
+
+/// Software filter settings of a single channel, the low cutoff is given as time constant in seconds
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct SoftwareFilterSetting {
+    pub id: usize,
+    /// Only present if the table has a name column
+    pub label: Option<String>,
+    pub low_cutoff: FilterSetting,
+    pub high_cutoff: FilterSetting,
+    pub notch: FilterSetting,
+}
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub enum SoftwareFilters {
+    #[default]
+    Disabled,
+    Enabled(Vec<SoftwareFilterSetting>),
+}
+
+/// Returns None if no "S o f t w a r e  F i l t e r s" block is present in the `[Comment]` text
+///
+/// The table rows end with the first empty line, like the other tables of the comment.
+/// Expects either the line `Disabled` or a table written by the recorder:
+/// ```text
+/// #     Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]
+/// 1     0.1592           70                 50
+/// ```
+pub fn parse_software_filters(textcontent: &str) -> Option<SoftwareFilters> {
+
+    let mut lines = textcontent.lines()
+        .map(|line| line.trim())
+        .skip_while(|line| !line.starts_with("S o f t w a r e  F i l t e r s"))
+        .skip(1)
+        // underline and blank lines between the title and the content
+        .skip_while(|line| line.is_empty() || line.starts_with('='));
+
+    let first_line = lines.next()?;
+    if first_line == "Disabled" {return Some(SoftwareFilters::Disabled)}
+    if !first_line.starts_with('#') {return None}
+
+    let has_label = first_line.contains("Name");
+    let settings = lines
+        .take_while(|line| !line.is_empty())
+        .map_while(|line| parse_software_filter_row(line, has_label))
+        .collect();
+    Some(SoftwareFilters::Enabled(settings))
+}
+
+/// Returns None if the row does not match the table layout
+pub fn parse_software_filter_row(line: &str, has_label: bool) -> Option<SoftwareFilterSetting> {
+    let mut columns = line.split_whitespace();
+    let id = columns.next()?.parse::<usize>().ok()?;
+    let label = if has_label {Some(columns.next()?.to_string())} else {None};
+
+    Some(SoftwareFilterSetting{
+        id,
+        label,
+        low_cutoff: parse_filter_setting(columns.next()?),
+        high_cutoff: parse_filter_setting(columns.next()?),
+        notch: parse_filter_setting(columns.next()?),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_software_filters_disabled() {
+        let input = "
+        S o f t w a r e  F i l t e r s
+        ==============================
+        Disabled
+
+
+        No impedance values available at 12:58:05!";
+        let output = parse_software_filters(input);
+        let expected = Some(SoftwareFilters::Disabled);
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_software_filters_enabled() {
+        let input = "
+        S o f t w a r e  F i l t e r s
+        ==============================
+        #     Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]
+        1      0.1592           70               50
+        2      Off              Off              Off
+
+        Impedance [kOhm] at 16:24:28 :";
+        let output = parse_software_filters(input);
+        let expected = Some(SoftwareFilters::Enabled(vec![
+            SoftwareFilterSetting{id: 1, label: None, low_cutoff: FilterSetting::ON(0.1592), high_cutoff: FilterSetting::ON(70.), notch: FilterSetting::ON(50.)},
+            SoftwareFilterSetting{id: 2, label: None, low_cutoff: FilterSetting::OFF, high_cutoff: FilterSetting::OFF, notch: FilterSetting::OFF},
+        ]));
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_software_filters_end() {
+        let input = "S o f t w a r e  F i l t e r s
+        ==============================
+        #     Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]
+        1      0.1592           70               50
+
+        2      Off              Off              Off";
+        let output = parse_software_filters(input).unwrap();
+        let SoftwareFilters::Enabled(settings) = output else {panic!("expected enabled filters")};
+        assert_eq!(settings.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_software_filters_labels() {
+        let input = "S o f t w a r e  F i l t e r s
+        ==============================
+        #     Name      Low Cutoff [s]   High Cutoff [Hz]   Notch [Hz]
+        1     Fp1       10               Off                50";
+        let output = parse_software_filters(input).unwrap();
+        let SoftwareFilters::Enabled(settings) = output else {panic!("expected enabled filters")};
+        assert_eq!(settings[0].label, Some("Fp1".to_string()));
+        assert_eq!(settings[0].low_cutoff, FilterSetting::ON(10.));
+    }
+
+    #[test]
+    fn test_parse_software_filters_empty() {
+        let input = "No impedance values available at 12:58:05!";
+        let output = parse_software_filters(input);
+        let expected = Option::None;
+        assert_eq!(output, expected);
+    }
+
+}
@@ -0,0 +1,81 @@
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::{split_fields, join_fields};
+
+/// One entry of the `[Channel User Infos]` or `[Marker User Infos]` sections of version 2.0 files
+/// 
+/// Each entry: Prop<Number>=<Ch|Mk><Number>,<Type>,<Name>,<Value>,<Value2>,...,<ValueN>
+/// 
+/// Types can be int, single, string, bool, byte, double, uint or arrays of those, indicated as int-array etc.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct UserProperty {
+    pub property_id: String,
+    /// Channel or marker the property belongs to, e.g. "Ch1" or "Mk1"
+    pub owner_id: String,
+    pub value_type: String,
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl UserProperty {
+    /// Returns the entry of the property, commas in names and values are escaped as "\\1"
+    pub fn to_entry(&self) -> String {
+        let fields = [&self.owner_id, &self.value_type, &self.name].into_iter()
+            .chain(self.values.iter())
+            .collect::<Vec<&String>>();
+        format!("{}={}", self.property_id, join_fields(&fields))
+    }
+}
+
+/// Returns empty Vec if the section is not present
+pub fn parse_user_infos(ini: &IniFile, section: &str) -> Vec<UserProperty> {
+
+    let mut results = vec![];
+    let Some(section) = ini.section(section) else {return results};
+
+    for (property_id, value) in section.entries.iter().filter(|(key, _)| key.starts_with("Prop")) {
+        let split_values = split_fields(value);
+        if split_values.len() < 3 {continue}
+
+        results.push(UserProperty{
+            property_id: property_id.to_string(),
+            owner_id: split_values[0].to_string(),
+            value_type: split_values[1].to_string(),
+            name: split_values[2].to_string(),
+            values: split_values[3..].iter().map(|v| v.to_string()).collect(),
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
+
+    #[test]
+    fn test_parse_user_infos() {
+        let input = "[Marker User Infos]
+        ; Each entry: Prop<Number>=Mk<Marker number>,<Type>,<Name>,<Value>,<Value2>,...,<ValueN>
+        Prop1=Mk2,int-array,Trial,3,4";
+        let output = parse_user_infos(&parse_ini(input), "Marker User Infos");
+        let expected = vec![UserProperty{
+            property_id: "Prop1".to_string(),
+            owner_id: "Mk2".to_string(),
+            value_type: "int-array".to_string(),
+            name: "Trial".to_string(),
+            values: vec!["3".to_string(), "4".to_string()],
+        }];
+        assert_eq!(output, expected);
+        assert_eq!(output[0].to_entry(), "Prop1=Mk2,int-array,Trial,3,4");
+    }
+
+    #[test]
+    fn test_parse_user_infos_empty() {
+        let input = "[Common Infos]
+        Codepage=UTF-8";
+        let output = parse_user_infos(&parse_ini(input), "Channel User Infos");
+        let expected = vec![];
+        assert_eq!(output, expected);
+    }
+
+}
@@ -0,0 +1,156 @@
+//!
+//! This module contains the section-aware tokenizer for the header and marker files
+//!
+//! Both `.vhdr` and `.vmrk` follow an ini-like layout: a version line, followed by `[Section]` headers
+//! containing `Key=Value` entries. Lines starting with `;` are comments and blank lines are ignored.
+//!
+//! The `[Comment]` section of the header is free text written by the recorder and is kept verbatim.
+//!
+
+/// Name of the section that is kept as free text
+pub const COMMENT_SECTION: &str = "Comment";
+
+/// One `[Section]` of the file with its entries in file order
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct IniSection {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+    /// Raw text of the section, only filled for free text sections like `[Comment]`
+    pub text: String,
+}
+
+impl IniSection {
+    /// Returns the value of the first entry with the given key
+    pub fn get_value(&self, key: &str) -> Option<&str> {
+        self.entries.iter()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Tokenized content of a header or marker file
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct IniFile {
+    /// First non-empty line of the file, e.g. `Brain Vision Data Exchange Header File Version 1.0`
+    pub version_line: String,
+    pub sections: Vec<IniSection>,
+}
+
+impl IniFile {
+    /// Returns the first section with the given name (without brackets)
+    pub fn section(&self, name: &str) -> Option<&IniSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Returns the value of `key` inside of `[section]`
+    pub fn get_value(&self, section: &str, key: &str) -> Option<&str> {
+        self.section(section).and_then(|section| section.get_value(key))
+    }
+
+    /// Returns the free text of the `[Comment]` section or an empty str if there is none
+    pub fn comment(&self) -> &str {
+        self.section(COMMENT_SECTION).map_or("", |section| section.text.as_str())
+    }
+}
+
+/// Splits the textcontent of a header or marker file into sections and entries
+///
+/// Never fails, lines that are neither a section header nor an entry are skipped
+pub fn parse_ini(textcontent: &str) -> IniFile {
+    let textcontent = textcontent.strip_prefix('\u{feff}').unwrap_or(textcontent);
+
+    let mut ini = IniFile::default();
+    let mut current: Option<IniSection> = None;
+
+    for line in textcontent.lines() {
+        let trimmed = line.trim();
+
+        if let Some(name) = parse_section_name(trimmed) {
+            if let Some(section) = current.take() {ini.sections.push(section);}
+            current = Some(IniSection{name: name.to_string(), ..Default::default()});
+            continue;
+        }
+
+        match current.as_mut() {
+            // free text is stored as is, so the comment parsers can see the original layout
+            Some(section) if section.name == COMMENT_SECTION => {
+                section.text.push_str(line);
+                section.text.push('\n');
+            },
+            Some(section) => {
+                if trimmed.is_empty() || trimmed.starts_with(';') {continue}
+                if let Some((key, value)) = trimmed.split_once('=') {
+                    section.entries.push((key.trim().to_string(), value.trim().to_string()));
+                }
+            },
+            None => {
+                if ini.version_line.is_empty() && !trimmed.is_empty() && !trimmed.starts_with(';') {
+                    ini.version_line = trimmed.to_string();
+                }
+            },
+        }
+    }
+    if let Some(section) = current.take() {ini.sections.push(section);}
+
+    ini
+}
+
+fn parse_section_name(line: &str) -> Option<&str> {
+    line.strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .filter(|name| !name.is_empty() && !name.contains(['[', ']']))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ini() {
+        let input = "Brain Vision Data Exchange Header File Version 1.0
+        ; Data created by the Vision Recorder
+
+        [Common Infos]
+        Codepage=UTF-8
+        ; Data orientation: MULTIPLEXED=ch1,pt1, ch2,pt1 ...
+        DataOrientation=MULTIPLEXED
+
+        [Channel Infos]
+        Ch1=Fp1,,0.0488281,µV";
+        let output = parse_ini(input);
+        assert_eq!(output.version_line, "Brain Vision Data Exchange Header File Version 1.0");
+        assert_eq!(output.sections.len(), 2);
+        assert_eq!(output.get_value("Common Infos", "Codepage"), Some("UTF-8"));
+        assert_eq!(output.get_value("Common Infos", "DataOrientation"), Some("MULTIPLEXED"));
+        assert_eq!(output.get_value("Channel Infos", "Ch1"), Some("Fp1,,0.0488281,µV"));
+    }
+
+    #[test]
+    fn test_parse_ini_wrong_section() {
+        let input = "[Channel Infos]
+        DataFile=wrong.eeg
+        [Common Infos]
+        DataFile=right.eeg";
+        let output = parse_ini(input);
+        assert_eq!(output.get_value("Common Infos", "DataFile"), Some("right.eeg"));
+    }
+
+    #[test]
+    fn test_parse_ini_comment() {
+        let input = "[Comment]
+        DataFile=not_an_entry.eeg
+        Reference Channel Name = Cz";
+        let output = parse_ini(input);
+        assert_eq!(output.get_value("Comment", "DataFile"), Option::None);
+        assert!(output.comment().contains("Reference Channel Name = Cz"));
+    }
+
+    #[test]
+    fn test_parse_ini_empty() {
+        let output = parse_ini("");
+        assert_eq!(output, IniFile::default());
+        assert_eq!(output.comment(), "");
+    }
+
+}
@@ -3,49 +3,82 @@
 use std::fs;
 //use std::fmt::Error;
 use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::ini_parser::parse_ini;
 
 pub mod parser;
 use parser::{
     MarkerData, 
+    MarkerType,
     parse_marker_data, 
     parse_marker_version::parse_marker_version, 
     parse_timecode::{BVTime, parse_timecode}};
 
 use crate::bv_reader::header::parser::{
-    parse_header_encoding::{parse_header_encoding, HeaderEncoding},
+    parse_header_encoding::{parse_header_encoding, decode_textcontent, HeaderEncoding},
     parse_filepaths::parse_datafilepath,
+    parse_header_version::HeaderVersion,
+    parse_user_infos::{UserProperty, parse_user_infos},
 };
 
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct BVMarker {
     pub marker_path: String,
-    pub header_version: String,
+    pub header_version: HeaderVersion,
 
     pub header_encoding: HeaderEncoding,
     pub data_file: String,
 
     pub marker_data: Vec<MarkerData>,
+    /// Only present in version 2.0
+    pub marker_user_infos: Vec<UserProperty>,
     pub start_time: Option<BVTime>,
 
 }
 
 impl BVMarker {
     pub fn from_file(filepath: &str) -> Result<Self, Error> {
-        let textcontent = fs::read_to_string(filepath);
-        if textcontent.is_err() {return Err(Error::FileRead(filepath.to_string()))}
-        let textcontent = textcontent.unwrap();
-        let header_version = parse_marker_version(&textcontent);
-        if header_version.is_none() {return Err(Error::InvalidHeaderVersion)};
+        let bytestring = fs::read(filepath);
+        if bytestring.is_err() {return Err(Error::FileRead(filepath.to_string()))}
+        let mut bv_marker = BVMarker::from_bytes(&bytestring.unwrap())?;
+        bv_marker.marker_path = filepath.to_string();
+        Ok(bv_marker)
+    }
+
+    /// Parses the raw content of a marker file, decoded as in [BVMarker::from_file]
+    pub fn from_bytes(bytestring: &[u8]) -> Result<Self, Error> {
+        BVMarker::from_text(&decode_textcontent(bytestring))
+    }
+
+    /// Parses the decoded content of a marker file, `marker_path` stays empty
+    pub fn from_text(textcontent: &str) -> Result<Self, Error> {
+        let ini = parse_ini(textcontent);
+        let header_version = parse_marker_version(&ini.version_line)?;
 
         Ok(BVMarker{
-            marker_path: filepath.to_string(),
-            header_version: header_version.unwrap(),
-            header_encoding: parse_header_encoding(&textcontent),
-            data_file: parse_datafilepath(&textcontent).unwrap(),
-            marker_data: parse_marker_data(&textcontent),
-            start_time: parse_timecode(&textcontent),
+            marker_path: String::new(),
+            header_version,
+            header_encoding: parse_header_encoding(&ini),
+            data_file: parse_datafilepath(&ini).unwrap_or_default(),
+            marker_data: parse_marker_data(&ini)?,
+            marker_user_infos: parse_user_infos(&ini, "Marker User Infos"),
+            start_time: parse_timecode(&ini),
         })
     }
+
+    /// Returns the dated "New Segment" marker the sample belongs to
+    ///
+    /// Marker positions are 1-based while `sample_idx` is 0-based. Samples before the first
+    /// dated segment are related to the first one.
+    pub fn segment_for_sample(&self, sample_idx: usize) -> Option<&MarkerData> {
+        let mut segments = self.marker_data.iter()
+            .filter(|marker| marker.marker_type == MarkerType::NewSegment && marker.marker_date.is_some());
+        let first = segments.next()?;
+        Some(std::iter::once(first)
+            .chain(segments)
+            .take_while(|marker| marker.marker_position <= sample_idx + 1)
+            .last()
+            .unwrap_or(first))
+    }
 }
 
 
@@ -59,16 +92,32 @@ mod tests {
         let output = BVMarker::from_file(input).unwrap();
         let expected = MarkerData{
             marker_id: "Mk2".to_string(),
-            marker_type: "Response".to_string(),
+            marker_type: MarkerType::Response,
             marker_description: "R  3".to_string(),
             marker_position: 8598,
             marker_length: 1,
             marker_chan: 0,
+            marker_date: None,
         };
-        //println!("{:?}", output);
+        assert_eq!(output.start_time, "20200316125805099157".parse::<BVTime>().ok());
         assert_eq!(output.marker_data[1], expected);
     }
 
+    #[test]
+    fn test_parse_marker_text() {
+        let input = "Brain Vision Data Exchange Marker File, Version 1.0
+        [Common Infos]
+        DataFile=Subject 01 (rest).eeg
+        [Marker Infos]
+        Mk1=New Segment,,1,1,0,20200316125805099157
+        Mk2=Comment,Hello\\1 World,5,1,0";
+        let output = BVMarker::from_text(input).unwrap();
+        assert_eq!(output.marker_path, "");
+        assert_eq!(output.data_file, "Subject 01 (rest).eeg");
+        assert_eq!(output.marker_data[1].marker_description, "Hello, World");
+        assert_eq!(output.start_time, "20200316125805099157".parse::<BVTime>().ok());
+    }
+
     #[test]
     fn test_parse_marker_empty() {
         let input = "";
@@ -77,4 +126,24 @@ mod tests {
         assert_eq!(output, expected);        
     }
 
+    #[test]
+    fn test_segment_for_sample() {
+        let segment = |id: &str, position: usize, date: &str| MarkerData{
+            marker_id: id.to_string(),
+            marker_type: MarkerType::NewSegment,
+            marker_position: position,
+            marker_date: date.parse::<BVTime>().ok(),
+            ..Default::default()
+        };
+        let input = BVMarker{marker_data: vec![
+            segment("Mk1", 1, "20200316125805099157"),
+            MarkerData{marker_id: "Mk2".to_string(), marker_position: 50, ..Default::default()},
+            segment("Mk3", 100, "20200316130000000000"),
+        ], ..Default::default()};
+        assert_eq!(input.segment_for_sample(0).unwrap().marker_id, "Mk1");
+        assert_eq!(input.segment_for_sample(98).unwrap().marker_id, "Mk1");
+        assert_eq!(input.segment_for_sample(99).unwrap().marker_id, "Mk3");
+        assert_eq!(BVMarker::default().segment_for_sample(0), Option::None);
+    }
+
 }
@@ -1,56 +1,126 @@
-use regex::Regex;
-use std::sync::OnceLock;
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::{split_fields, join_fields};
+use crate::bv_reader::bv_error::Error;
+use parse_timecode::BVTime;
 
 pub mod parse_marker_version;
 pub mod parse_timecode;
 
 
+/// Marker types written by the recorder, any other type is kept as `Custom`
+#[derive(Default, Debug, PartialEq, Eq, Clone)]
+pub enum MarkerType {
+    #[default]
+    Stimulus,
+    Response,
+    NewSegment,
+    Comment,
+    BadInterval,
+    TimeZero,
+    DCCorrection,
+    SyncStatus,
+    Custom(String),
+}
+
+impl MarkerType {
+    /// Returns the type as written in the marker file
+    pub fn as_str(&self) -> &str {
+        match self {
+            MarkerType::Stimulus => "Stimulus",
+            MarkerType::Response => "Response",
+            MarkerType::NewSegment => "New Segment",
+            MarkerType::Comment => "Comment",
+            MarkerType::BadInterval => "Bad Interval",
+            MarkerType::TimeZero => "Time 0",
+            MarkerType::DCCorrection => "DC Correction",
+            MarkerType::SyncStatus => "SyncStatus",
+            MarkerType::Custom(name) => name.as_str(),
+        }
+    }
+}
+
+pub fn parse_marker_type(input: &str) -> MarkerType {
+    match input {
+        "Stimulus" => MarkerType::Stimulus,
+        "Response" => MarkerType::Response,
+        "New Segment" => MarkerType::NewSegment,
+        "Comment" => MarkerType::Comment,
+        "Bad Interval" => MarkerType::BadInterval,
+        "Time 0" => MarkerType::TimeZero,
+        "DC Correction" => MarkerType::DCCorrection,
+        "SyncStatus" => MarkerType::SyncStatus,
+        x => MarkerType::Custom(x.to_string()),
+    }
+}
+
+
+/// One entry of `[Marker Infos]`: Mk<Number>=<Type>,<Description>,<Position>,<Size>,<Channel>[,<Date>]
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct MarkerData {
     pub marker_id: String,
-    pub marker_type: String,
+    pub marker_type: MarkerType,
     pub marker_description: String,
     pub marker_position: usize,
+    /// Defaults to 1 if omitted
     pub marker_length: usize,
+    /// 0 = marker is related to all channels, default if omitted
     pub marker_chan: usize,
+    /// Usually only present for "New Segment" markers
+    pub marker_date: Option<BVTime>,
 }
 
 
-static MARKER_DATA_REGEX: OnceLock<Regex> = OnceLock::new();
+impl MarkerData {
+    /// Returns the `[Marker Infos]` entry of the marker, commas in type and description are escaped as "\\1"
+    pub fn to_entry(&self) -> String {
+        let mut fields = vec![
+            self.marker_type.as_str().to_string(),
+            self.marker_description.clone(),
+            self.marker_position.to_string(),
+            self.marker_length.to_string(),
+            self.marker_chan.to_string(),
+        ];
+        if let Some(date) = &self.marker_date {fields.push(date.timecode.clone())}
+        format!("{}={}", self.marker_id, join_fields(&fields))
+    }
+}
+
+
+/// Returns an empty vector if no markers were present in `[Marker Infos]`
+///
+/// Empty entries are skipped, omitted size and channel fields get their default values.
+/// A missing or malformed date is returned as `None`.
+/// Returns Error::InvalidMarker for entries without a type or position, or with a malformed number.
+pub fn parse_marker_data(ini: &IniFile) -> Result<Vec<MarkerData>, Error> {
 
-/// Returns an empty vector if no markers were present
-pub fn parse_marker_data(textcontent: &str) -> Vec<MarkerData> {
-    
-    let re = MARKER_DATA_REGEX.get_or_init(|| {
-        Regex::new(r"(\w*)=([\w\ ]*),([\w\ ]*),(\d*),(\d*),(\d*)").unwrap() 
-      });
-    
     let mut results = vec![];
+    let Some(section) = ini.section("Marker Infos") else {return Ok(results)};
+
+    // Iterate over entries and extract components
+    for (marker_id, value) in section.entries.iter().filter(|(key, _)| key.starts_with("Mk")) {
+        if value.is_empty() {continue}
+        let split_values = split_fields(value);
+        let invalid = || Error::InvalidMarker(format!("{marker_id}={value}"));
+        let field = |idx: usize| split_values.get(idx).map(|x| x.as_str()).filter(|x| !x.is_empty());
+
+        let marker_type = field(0).map(parse_marker_type).ok_or_else(invalid)?;
+        let marker_position = field(2).and_then(|x| x.parse::<usize>().ok()).ok_or_else(invalid)?;
+        let marker_length = field(3).map_or(Ok(1), |x| x.parse::<usize>()).map_err(|_| invalid())?;
+        let marker_chan = field(4).map_or(Ok(0), |x| x.parse::<usize>()).map_err(|_| invalid())?;
+        // Analyzer exports write an all-zero date for segments without a start time, the date is optional anyway
+        let marker_date = field(5).and_then(|date| date.parse::<BVTime>().ok());
 
-    // Iterate over matches and extract components
-    for caps in re.captures_iter(textcontent) {
-        let marker_id = caps.get(1).map(|m| m.as_str());
-        if marker_id.is_some() { // serves as validation that we can safely unwrap everything in this line
-            let marker_id = marker_id.unwrap().to_string();
-            let marker_type = caps.get(2).map(|m| m.as_str().to_string()).unwrap();
-            let marker_description = caps.get(3).map(|m| m.as_str().to_string()).unwrap();
-            let marker_position = caps.get(4).map(|m| m.as_str().parse::<usize>().unwrap()).unwrap();
-            let marker_length = caps.get(5).map(|m| m.as_str().parse::<usize>().unwrap()).unwrap();
-            let marker_chan = caps.get(6).map(|m| m.as_str().parse::<usize>().unwrap()).unwrap();
-
-            let chan = MarkerData { 
-                marker_id, 
-                marker_type, 
-                marker_description, 
-                marker_position, 
-                marker_length, 
-                marker_chan};
-
-            results.push(chan);
-            
-        }    
+        results.push(MarkerData {
+            marker_id: marker_id.to_string(),
+            marker_type,
+            marker_description: field(1).unwrap_or_default().to_string(),
+            marker_position,
+            marker_length,
+            marker_chan,
+            marker_date,
+        });
     }
-    results
+    Ok(results)
 
 }
 
@@ -58,37 +128,118 @@ pub fn parse_marker_data(textcontent: &str) -> Vec<MarkerData> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_marker_data() {
-        let input = "; Fields are delimited by commas, some fields might be omitted (empty).
+        let input = "[Marker Infos]
+        ; Fields are delimited by commas, some fields might be omitted (empty).
         ; Commas in type or description text are coded as \"\\1\".
         Mk1=New Segment,,1,1,0,20200316125805099157
         Mk2=Response,R  3,8598,1,0
         Mk3=Response,R  2,10854,1,0";
-        let output = parse_marker_data(input);
+        let output = parse_marker_data(&parse_ini(input)).unwrap();
         let expected = MarkerData{
             marker_id: "Mk2".to_string(),
-            marker_type: "Response".to_string(),
+            marker_type: MarkerType::Response,
             marker_description: "R  3".to_string(),
             marker_position: 8598,
             marker_length: 1,
             marker_chan: 0,
+            marker_date: None,
         };
-        println!("{:?}", output[0]);
+        assert_eq!(output[0].marker_type, MarkerType::NewSegment);
+        assert_eq!(output[0].marker_date, "20200316125805099157".parse::<BVTime>().ok());
         assert_eq!(output[1], expected);
     }
 
+    #[test]
+    fn test_parse_marker_data_zero_date() {
+        let input = "[Marker Infos]
+        Mk1=New Segment,,1,1,0,00000000000000000000
+        Mk2=Stimulus,S  1,5,1,0";
+        let output = parse_marker_data(&parse_ini(input)).unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].marker_type, MarkerType::NewSegment);
+        assert_eq!(output[0].marker_date, Option::None);
+    }
+
     #[test]
     fn test_parse_marker_data_empty() {
-        let input = "; Fields are delimited by commas, some fields might be omitted (empty).
+        let input = "[Marker Infos]
+        ; Fields are delimited by commas, some fields might be omitted (empty).
         ; Commas in type or description text are coded as \"\\1\".
         Mk1=
         Mk2=
         Mk3=";
-        let output = parse_marker_data(input);
+        let output = parse_marker_data(&parse_ini(input)).unwrap();
         let expected = vec![];
         assert_eq!(output, expected);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_parse_marker_data_escaped() {
+        let input = "[Marker Infos]
+        Mk1=Comment,left\\1 right,12,1,0
+        Mk2=Stimulus,S-1/ä,20,1,3";
+        let output = parse_marker_data(&parse_ini(input)).unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].marker_description, "left, right");
+        assert_eq!(output[1].marker_description, "S-1/ä");
+        assert_eq!(output[1].marker_chan, 3);
+    }
+
+    #[test]
+    fn test_marker_data_to_entry() {
+        let input = MarkerData{
+            marker_id: "Mk1".to_string(),
+            marker_type: MarkerType::Comment,
+            marker_description: "left, right".to_string(),
+            marker_position: 12,
+            marker_length: 1,
+            marker_chan: 0,
+            marker_date: None,
+        };
+        let output = input.to_entry();
+        let expected = "Mk1=Comment,left\\1 right,12,1,0";
+        assert_eq!(output, expected);
+        assert_eq!(parse_marker_data(&parse_ini(&format!("[Marker Infos]\n{output}"))).unwrap(), vec![input]);
+    }
+
+    #[test]
+    fn test_parse_marker_data_types() {
+        let input = "[Marker Infos]
+        Mk1=Bad Interval,Hello-World,5,20
+        Mk2=Time 0,,7,,
+        Mk3=Stimulus,S255/x,9,1,0
+        Mk4=Voice,,11,1,0";
+        let output = parse_marker_data(&parse_ini(input)).unwrap();
+        assert_eq!(output.len(), 4);
+        assert_eq!(output[0].marker_type, MarkerType::BadInterval);
+        assert_eq!(output[0].marker_length, 20);
+        assert_eq!(output[0].marker_chan, 0);
+        assert_eq!(output[1].marker_type, MarkerType::TimeZero);
+        assert_eq!(output[1].marker_length, 1);
+        assert_eq!(output[2].marker_description, "S255/x");
+        assert_eq!(output[3].marker_type, MarkerType::Custom("Voice".to_string()));
+        assert_eq!(output[3].to_entry(), "Mk4=Voice,,11,1,0");
+    }
+
+    #[test]
+    fn test_parse_marker_data_invalid() {
+        let input = "[Marker Infos]
+        Mk1=Stimulus,S  1,x,1,0";
+        let output = parse_marker_data(&parse_ini(input));
+        let expected = Err(Error::InvalidMarker("Mk1=Stimulus,S  1,x,1,0".to_string()));
+        assert_eq!(output, expected);
+
+        let input = "[Marker Infos]
+        Mk1=New Segment,,1,1,0,x
+        Mk2=New Segment,,5,1,0,20201316125805099157";
+        let output = parse_marker_data(&parse_ini(input)).unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].marker_date, Option::None);
+        assert_eq!(output[1].marker_date, Option::None);
+    }
+
+}
@@ -1,22 +1,22 @@
 use regex::Regex;
 use std::sync::OnceLock;
 
+use crate::bv_reader::bv_error::Error;
+use crate::bv_reader::header::parser::parse_header_version::HeaderVersion;
+
 static MARKER_VERSION_REGEX: OnceLock<Regex> = OnceLock::new();
 
-/// Returns an empty String if no version was found
-pub fn parse_marker_version(textcontent: &str) -> Option<String> {
+/// Returns Error::InvalidHeaderVersion if no version was found and Error::UnsupportedHeaderVersion if the version is not known
+pub fn parse_marker_version(textcontent: &str) -> Result<HeaderVersion, Error> {
 
     let re = MARKER_VERSION_REGEX.get_or_init(|| {
         Regex::new(r"Brain Vision Data Exchange Marker File, Version (\d{1,}\.\d{1,})").unwrap() 
       });
     
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(Option::None, |m| Some(m.as_str().to_string()))
-    } else {
-        Option::None
-    }
+    let version = re.captures(textcontent)
+        .and_then(|caps| caps.get(1))
+        .ok_or(Error::InvalidHeaderVersion)?;
+    HeaderVersion::from_version_str(version.as_str())
 }
 
 #[cfg(test)]
@@ -27,7 +27,15 @@ mod tests {
     fn test_parse_marker_version() {
         let input = "Brain Vision Data Exchange Marker File, Version 1.0";
         let output = parse_marker_version(input).unwrap();
-        let expected = "1.0".to_string();
+        let expected = HeaderVersion::V1_0;
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_marker_version_2() {
+        let input = "Brain Vision Data Exchange Marker File, Version 2.0";
+        let output = parse_marker_version(input).unwrap();
+        let expected = HeaderVersion::V2_0;
         assert_eq!(output, expected);
     }
 
@@ -35,8 +43,8 @@ mod tests {
     fn test_parse_marker_version_empty() {
         let input = "Brain Vision Data Exchange Marker File Version";
         let output = parse_marker_version(input);
-        let expected = Option::None;
+        let expected = Err(Error::InvalidHeaderVersion);
         assert_eq!(output, expected);
     }
 
-}
\ No newline at end of file
+}
@@ -1,8 +1,9 @@
-use regex::Regex;
-use std::sync::OnceLock;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-static TIMECODE_REGEX: OnceLock<Regex> = OnceLock::new();
+use crate::bv_reader::ini_parser::IniFile;
+use crate::bv_reader::generic_parser::split_fields;
+use crate::bv_reader::bv_error::Error;
 
 /// Type of BVTime after [specification](https://www.fieldtriptoolbox.org/assets/pdf/BrainVisionCoreFileFormat_1.0_2018-08-02.pdf)
 /// 
@@ -29,7 +30,8 @@ static TIMECODE_REGEX: OnceLock<Regex> = OnceLock::new();
 /// 
 /// 6 digits = microsecond
 /// 
-/// The result is a time resolution of a microsecond.
+/// The result is a time resolution of a microsecond. All conversions use the proleptic
+/// gregorian calendar in UTC.
 /// 
 /// Example
 /// 
@@ -41,108 +43,184 @@ pub struct BVTime {
     pub timecode: String
 }
 
+const SECONDS_PER_DAY: i64 = 86_400;
+
 impl BVTime{
-    /// Returns Option<None> when timecode has over 20 characters
-    pub fn new(timecode: String) -> Option<Self> {
-        if timecode.len() != 20 {
-            return None; // Invalid timecode length
+    /// Returns Error::InvalidTimecode if the timecode is not 20 digits or contains an invalid date or time
+    pub fn new(timecode: String) -> Result<Self, Error> {
+        if timecode.len() != 20 || !timecode.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidTimecode(timecode));
         }
-        Some(BVTime{timecode})
+        let bv_time = BVTime{timecode};
+        let (year, month, day, hour, minute, second, _) = bv_time.components();
+        let valid = (1..=12).contains(&month)
+            && day >= 1 && day <= days_in_month(year, month)
+            && hour < 24 && minute < 60 && second < 60;
+        if !valid {return Err(Error::InvalidTimecode(bv_time.timecode))}
+        Ok(bv_time)
     }
 
-    pub fn from_str(timecode: &str) -> Option<Self> {
-        BVTime::new(timecode.to_string())
+    /// Builds the timecode from its components, fails on invalid dates like the 30th of February
+    pub fn from_components(year: u32, month: u32, day: u32, hour: u32, minute: u32, second: u32, microsecond: u32) -> Result<Self, Error> {
+        if year > 9999 || microsecond > 999_999 {
+            return Err(Error::InvalidTimecode(format!("{year}-{month}-{day} {hour}:{minute}:{second}.{microsecond}")));
+        }
+        BVTime::new(format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}{:06}",
+            year, month, day, hour, minute, second, microsecond
+        ))
     }
 
+    /// Returns (year, month, day, hour, minute, second, microsecond)
+    ///
+    /// Components that can not be parsed are returned as 0
+    pub fn components(&self) -> (u32, u32, u32, u32, u32, u32, u32) {
+        let part = |range: std::ops::Range<usize>| self.timecode.get(range).and_then(|x| x.parse::<u32>().ok()).unwrap_or_default();
+        (part(0..4), part(4..6), part(6..8), part(8..10), part(10..12), part(12..14), part(14..20))
+    }
+
+    /// Returns Option<None> if the timecode was modified to an invalid value after construction
     pub fn to_system_time(&self) -> Option<SystemTime> {
-        let year: u32 = self.timecode[0..4].parse().ok()?;
-        let month: u32 = self.timecode[4..6].parse().ok()?;
-        let day: u32 = self.timecode[6..8].parse().ok()?;
-        let hour: u32 = self.timecode[8..10].parse().ok()?;
-        let min: u32 = self.timecode[10..12].parse().ok()?;
-        let sec: u32 = self.timecode[12..14].parse().ok()?;
-        let subsec: u32 = self.timecode[14..20].parse().ok()?;
-    
-        // Calculate the total seconds since UNIX_EPOCH
-        let total_seconds = (year - 1970) as u64 * 31556926 + // seconds in a year
-                             ((month - 1) as u64 * 2629743) +        // seconds in a month
-                             ((day - 1) as u64 * 86400) +            // seconds in a day
-                             (hour as u64 * 3600) +            // seconds in an hour
-                             (min as u64 * 60) +               // seconds in a minute
-                             sec as u64;
-    
-        // Create a SystemTime instance
-        let timestamp = UNIX_EPOCH + std::time::Duration::from_secs(total_seconds) +
-                        std::time::Duration::from_nanos(subsec as u64);
-    
-        Some(timestamp)
+        let bv_time = BVTime::new(self.timecode.clone()).ok()?;
+        let (year, month, day, hour, minute, second, microsecond) = bv_time.components();
+
+        let total_seconds = days_from_civil(year as i64, month, day) * SECONDS_PER_DAY
+            + (hour * 3600 + minute * 60 + second) as i64;
+
+        let timestamp = if total_seconds >= 0 {
+            UNIX_EPOCH + Duration::from_secs(total_seconds as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(total_seconds.unsigned_abs())
+        };
+        Some(timestamp + Duration::from_micros(microsecond as u64))
     }
 
+    /// Sub-microsecond precision is truncated
+    ///
+    /// Returns Option<None> if the time is outside of the years 0 to 9999
     pub fn from_system_time(system_time: SystemTime) -> Option<Self> {
-        // Calculate the total seconds and nanoseconds since UNIX_EPOCH
-        let duration_since_epoch = match system_time.duration_since(UNIX_EPOCH) {
-            Ok(duration) => duration,
-            Err(_) => return None, // The provided time is before UNIX_EPOCH
+        // whole microseconds relative to UNIX_EPOCH, rounded towards the past
+        let total_micros: i128 = match system_time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => (duration.as_nanos() / 1_000) as i128,
+            Err(err) => -((err.duration().as_nanos() as i128 + 999) / 1_000),
         };
-        let total_seconds = duration_since_epoch.as_secs();
-        let subsec_nanos = duration_since_epoch.subsec_nanos();
-
-        // Calculate years, months, days, hours, minutes, and seconds from total_seconds
-        let year = 1970 + (total_seconds / 31556926) as u32;
-        let mut remaining_seconds = total_seconds % 31556926;
-        let month = remaining_seconds / 2629743 + 1;
-        remaining_seconds %= 2629743;
-        let day = remaining_seconds / 86400 + 1;
-        remaining_seconds %= 86400;
-        let hour = remaining_seconds / 3600;
-        remaining_seconds %= 3600;
-        let min = remaining_seconds / 60;
-        let sec = remaining_seconds % 60;
-
-        // Format the components into a timecode string
-        let timecode = format!(
-            "{:04}{:02}{:02}{:02}{:02}{:02}{:06}",
-            year, month, day, hour, min, sec, subsec_nanos
-        );
+        let total_seconds = total_micros.div_euclid(1_000_000) as i64;
+        let microsecond = total_micros.rem_euclid(1_000_000) as u32;
+
+        let days = total_seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = total_seconds.rem_euclid(SECONDS_PER_DAY) as u32;
+        let (year, month, day) = civil_from_days(days);
+        if !(0..=9999).contains(&year) {return None}
+
+        BVTime::from_components(
+            year as u32, month, day,
+            seconds_of_day / 3600, seconds_of_day % 3600 / 60, seconds_of_day % 60,
+            microsecond,
+        ).ok()
+    }
+
+    /// Returns the time shifted by a (possibly negative) number of microseconds
+    pub fn add_micros(&self, micros: i64) -> Option<Self> {
+        let system_time = self.to_system_time()?;
+        let shifted = if micros >= 0 {
+            system_time.checked_add(Duration::from_micros(micros as u64))?
+        } else {
+            system_time.checked_sub(Duration::from_micros(micros.unsigned_abs()))?
+        };
+        BVTime::from_system_time(shifted)
+    }
+}
+
+impl FromStr for BVTime {
+    type Err = Error;
 
-        BVTime::new(timecode)
+    fn from_str(timecode: &str) -> Result<Self, Self::Err> {
+        BVTime::new(timecode.to_string())
     }
 }
 
-/// Returns Option<None> if no timecode was found
-pub fn parse_timecode(textcontent: &str) -> Option<BVTime> {
+fn is_leap_year(year: u32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
 
-    let re = TIMECODE_REGEX.get_or_init(|| {
-        Regex::new(r"Mk1=New Segment,,\d*,\d*,\d*,(\d*)").unwrap() 
-      });
-    
-    let caps = re.captures(textcontent);
-    if caps.is_some() {
-        let caps = caps.unwrap();
-        caps.get(1).map_or(Option::None, |m| BVTime::from_str(m.as_str()))
-    } else {
-        Option::None
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
     }
 }
 
+/// Days since 1970-01-01 in the proleptic gregorian calendar, after Howard Hinnant's `days_from_civil`
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 {year - 1} else {year};
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = (month as i64 + 9) % 12; // march = 0
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of [days_from_civil], returns (year, month, day)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {month_index + 3} else {month_index - 9} as u32;
+    let year = year_of_era + era * 400 + if month <= 2 {1} else {0};
+    (year, month, day)
+}
+
+/// Returns the date of the first "New Segment" marker in `[Marker Infos]`
+///
+/// Returns Option<None> if no New Segment marker with a valid date is present
+pub fn parse_timecode(ini: &IniFile) -> Option<BVTime> {
+
+    let section = ini.section("Marker Infos")?;
+    section.entries.iter()
+        .map(|(_, value)| split_fields(value))
+        .filter(|fields| fields.first().is_some_and(|marker_type| marker_type == "New Segment"))
+        .find_map(|fields| fields.get(5).and_then(|date| date.parse::<BVTime>().ok()))
+}
+
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bv_reader::ini_parser::parse_ini;
 
     #[test]
     fn test_parse_timecode() {
-        let input = "Mk1=New Segment,,1,1,0,20200316125805099157";
-        let output = parse_timecode(input).unwrap();
+        let input = "[Marker Infos]
+        Mk1=New Segment,,1,1,0,20200316125805099157";
+        let output = parse_timecode(&parse_ini(input)).unwrap();
+        let expected = BVTime::from_str("20200316125805099157").unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_parse_timecode_later_segment() {
+        let input = "[Marker Infos]
+        Mk1=Comment,start,1,1,0
+        Mk2=New Segment,,1,1,0,20200316125805099157";
+        let output = parse_timecode(&parse_ini(input)).unwrap();
         let expected = BVTime::from_str("20200316125805099157").unwrap();
         assert_eq!(output, expected);
     }
 
     #[test]
     fn test_parse_timecode_empty() {
-        let input = "Mk1=New Segment,,1,1,0";
-        let output = parse_timecode(input);
+        let input = "[Marker Infos]
+        Mk1=New Segment,,1,1,0";
+        let output = parse_timecode(&parse_ini(input));
         let expected = Option::None;
         assert_eq!(output, expected);
     }
@@ -164,4 +242,60 @@ mod tests {
         assert_eq!(timecode, expected);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_timecode_to_unix_time_calendar() {
+        // 2020-03-16T12:58:05.099157Z
+        let timecode = BVTime::from_str("20200316125805099157").unwrap();
+        let timestamp = timecode.to_system_time().unwrap();
+        let expected = UNIX_EPOCH + Duration::new(1_584_363_485, 99_157_000);
+        assert_eq!(timestamp, expected);
+    }
+
+    #[test]
+    fn test_timecode_leap_years() {
+        // 2000 is a leap year, 1900 and 2100 are not
+        let timestamp = BVTime::from_str("20000229235959999999").unwrap().to_system_time().unwrap();
+        let expected = UNIX_EPOCH + Duration::new(951_868_799, 999_999_000);
+        assert_eq!(timestamp, expected);
+        assert!(BVTime::from_str("19000229000000000000").is_err());
+        assert!(BVTime::from_str("21000229000000000000").is_err());
+        assert!(BVTime::from_str("20240229000000000000").is_ok());
+    }
+
+    #[test]
+    fn test_timecode_before_unix_epoch() {
+        let timecode = BVTime::from_str("19691231235959500000").unwrap();
+        let timestamp = timecode.to_system_time().unwrap();
+        let expected = UNIX_EPOCH - Duration::from_millis(500);
+        assert_eq!(timestamp, expected);
+        assert_eq!(BVTime::from_system_time(timestamp).unwrap(), timecode);
+    }
+
+    #[test]
+    fn test_timecode_from_system_time_micros() {
+        let input = UNIX_EPOCH + Duration::new(1_584_363_485, 99_157_999);
+        let output = BVTime::from_system_time(input).unwrap();
+        let expected = BVTime::from_str("20200316125805099157").unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_timecode_invalid() {
+        let expected = Err(Error::InvalidTimecode("20201316125805099157".to_string()));
+        assert_eq!("20201316125805099157".parse::<BVTime>(), expected);
+        assert!(BVTime::from_str("20200431000000000000").is_err());
+        assert!(BVTime::from_str("20200101240000000000").is_err());
+        assert!(BVTime::from_str("2020010100000000000x").is_err());
+        assert!(BVTime::from_str("2020").is_err());
+    }
+
+    #[test]
+    fn test_timecode_add_micros() {
+        let timecode = BVTime::from_str("20201231235959999000").unwrap();
+        let output = timecode.add_micros(2_000).unwrap();
+        let expected = BVTime::from_str("20210101000000001000").unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(output.add_micros(-2_000).unwrap(), timecode);
+    }
+
+}
@@ -0,0 +1,385 @@
+//!
+//! This module contains standard electrode positions for headers without a `[Coordinates]` section
+//!
+//! Positions are computed on an idealized spherical head: the midline from Nz to Iz spans 225°, so 5% of it
+//! equal 11.25°, and the 10-05 rows are divided into equal arcs between the midline and the equator.
+//! Angles follow the `[Coordinates]` convention of [SphericalPosition].
+//!
+//! These positions are approximate. They are not the measured 10-05 tables of Oostenveld and Praamstra or of a
+//! cap manufacturer and can differ from them by several degrees, most of all in the lower rows. They are meant for
+//! topographic plots and channel neighbourhoods, not for source localisation, which needs digitized positions.
+//!
+
+use crate::bv_reader::header::parser::parse_coordinates::{SphericalPosition, CartesianPosition};
+
+/// Rows from front to back with their midline offset from Cz in 5% steps, and the row name of the temporal columns
+const ROWS: [(&str, i32, &str); 17] = [
+    ("Fp", 8, "Fp"),
+    ("AFp", 7, "AFp"),
+    ("AF", 6, "AF"),
+    ("AFF", 5, "AFF"),
+    ("F", 4, "F"),
+    ("FFC", 3, "FFT"),
+    ("FC", 2, "FT"),
+    ("FCC", 1, "FTT"),
+    ("C", 0, "T"),
+    ("CCP", -1, "TTP"),
+    ("CP", -2, "TP"),
+    ("CPP", -3, "TPP"),
+    ("P", -4, "P"),
+    ("PPO", -5, "PPO"),
+    ("PO", -6, "PO"),
+    ("POO", -7, "POO"),
+    ("O", -8, "O"),
+];
+
+/// Old 10-20 names and names of positions that coincide in the 10-05 system
+const ALIASES: [(&str, &str); 6] = [
+    ("T3", "T7"),
+    ("T4", "T8"),
+    ("T5", "P7"),
+    ("T6", "P8"),
+    ("I1", "O9"),
+    ("I2", "O10"),
+];
+
+/// Electrodes of the actiCAP 32 channel standard layout in channel order, followed by the reference FCz
+///
+/// The ground electrode Fpz is not recorded.
+const ACTICAP_32: [&str; 33] = [
+    "Fp1", "Fz", "F3", "F7", "FT9", "FC5", "FC1", "C3", "T7", "TP9", "CP5", "CP1", "Pz", "P3", "P7", "O1",
+    "Oz", "O2", "P4", "P8", "TP10", "CP6", "CP2", "Cz", "C4", "T8", "FT10", "FC6", "FC2", "F4", "F8", "Fp2",
+    "FCz",
+];
+
+/// Electrodes of the actiCAP 64 channel standard layout in channel order, followed by the reference FCz
+///
+/// Channels 1 to 32 are the 32 channel layout. The ground electrode Fpz is not recorded.
+const ACTICAP_64: [&str; 65] = [
+    "Fp1", "Fz", "F3", "F7", "FT9", "FC5", "FC1", "C3", "T7", "TP9", "CP5", "CP1", "Pz", "P3", "P7", "O1",
+    "Oz", "O2", "P4", "P8", "TP10", "CP6", "CP2", "Cz", "C4", "T8", "FT10", "FC6", "FC2", "F4", "F8", "Fp2",
+    "AF7", "AF3", "AFz", "F1", "F5", "FT7", "FC3", "C1", "C5", "TP7", "CP3", "P1", "P5", "PO7", "PO3", "POz",
+    "PO4", "PO8", "P6", "P2", "CPz", "CP4", "TP8", "C6", "C2", "FC4", "FT8", "F6", "AF8", "AF4", "F2", "Iz",
+    "FCz",
+];
+
+/// Electrodes of the EasyCap / BrainCap 32 channel standard layout in channel order, followed by the reference FCz
+///
+/// Channel 32 records the ECG and has no position, it is left out. The ground electrode AFz is not recorded.
+const EASYCAP_32: [&str; 32] = [
+    "Fp1", "Fp2", "F3", "F4", "C3", "C4", "P3", "P4", "O1", "O2", "F7", "F8", "T7", "T8", "P7", "P8",
+    "Fz", "Cz", "Pz", "Oz", "FC1", "FC2", "CP1", "CP2", "FC5", "FC6", "CP5", "CP6", "TP9", "TP10", "POz",
+    "FCz",
+];
+
+/// Electrodes of the EasyCap / BrainCap 64 channel standard layout in channel order, followed by the reference FCz
+///
+/// Channels 1 to 31 are the 32 channel layout, channel 32 records the ECG and is left out.
+/// The ground electrode AFz is not recorded.
+const EASYCAP_64: [&str; 64] = [
+    "Fp1", "Fp2", "F3", "F4", "C3", "C4", "P3", "P4", "O1", "O2", "F7", "F8", "T7", "T8", "P7", "P8",
+    "Fz", "Cz", "Pz", "Oz", "FC1", "FC2", "CP1", "CP2", "FC5", "FC6", "CP5", "CP6", "TP9", "TP10", "POz",
+    "F1", "F2", "C1", "C2", "P1", "P2", "AF3", "AF4", "FC3", "FC4", "CP3", "CP4", "PO3", "PO4", "F5",
+    "F6", "C5", "C6", "P5", "P6", "AF7", "AF8", "FT7", "FT8", "TP7", "TP8", "PO7", "PO8", "FT9", "FT10",
+    "Fpz", "CPz", "FCz",
+];
+
+const STANDARD_1020: [&str; 21] = [
+    "Fp1", "Fpz", "Fp2", "F7", "F3", "Fz", "F4", "F8", "T7", "C3", "Cz", "C4", "T8", "P7", "P3", "Pz",
+    "P4", "P8", "O1", "Oz", "O2",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Montage {
+    Standard1020,
+    Standard1010,
+    Standard1005,
+    /// actiCAP / actiCHamp 32 channel standard layout
+    ActiCap32,
+    /// actiCAP / actiCHamp 64 channel standard layout
+    ActiCap64,
+    /// EasyCap / BrainCap 32 channel standard layout for BrainAmp amplifiers
+    EasyCap32,
+    /// EasyCap / BrainCap 64 channel standard layout for BrainAmp amplifiers
+    EasyCap64,
+}
+
+impl Montage {
+    /// Returns the electrode labels of the montage
+    pub fn labels(&self) -> Vec<String> {
+        match self {
+            Montage::Standard1020 => STANDARD_1020.iter().map(|label| label.to_string()).collect(),
+            Montage::Standard1010 => grid_labels(false),
+            Montage::Standard1005 => grid_labels(true),
+            Montage::ActiCap32 => ACTICAP_32.iter().map(|label| label.to_string()).collect(),
+            Montage::ActiCap64 => ACTICAP_64.iter().map(|label| label.to_string()).collect(),
+            Montage::EasyCap32 => EASYCAP_32.iter().map(|label| label.to_string()).collect(),
+            Montage::EasyCap64 => EASYCAP_64.iter().map(|label| label.to_string()).collect(),
+        }
+    }
+
+    /// Returns the position of an electrode of the montage, labels are matched case-insensitively
+    pub fn position(&self, label: &str) -> Option<SphericalPosition> {
+        let label = canonical_label(label);
+        if !self.labels().iter().any(|known| known.eq_ignore_ascii_case(&label)) {return None}
+        standard_position(&label)
+    }
+
+    /// Returns the positions of all labels and the labels that are not part of the montage
+    pub fn match_labels<S: AsRef<str>>(&self, labels: &[S]) -> (Vec<Option<SphericalPosition>>, Vec<String>) {
+        let known: Vec<String> = self.labels().iter().map(|label| label.to_lowercase()).collect();
+        let mut unmatched = vec![];
+        let positions = labels.iter().map(|label| {
+            let label = label.as_ref();
+            let canonical = canonical_label(label);
+            let position = known.contains(&canonical.to_lowercase()).then(|| standard_position(&canonical)).flatten();
+            if position.is_none() {unmatched.push(label.to_string())}
+            position
+        }).collect();
+        (positions, unmatched)
+    }
+}
+
+/// Returns the approximate position of any 10-05 label on the idealized sphere, matched case-insensitively
+///
+/// Also accepts the old 10-20 names T3, T4, T5 and T6. See the module documentation for how far the positions can be off.
+pub fn standard_position(label: &str) -> Option<SphericalPosition> {
+    let label = canonical_label(label).to_lowercase();
+
+    match label.as_str() {
+        "nz" => return Some(SphericalPosition{radius: 1., theta: 112.5, phi: 90.}),
+        "iz" => return Some(SphericalPosition{radius: 1., theta: 112.5, phi: -90.}),
+        _ => {},
+    }
+
+    // longest row name first, e.g. "FCC" before "FC" before "F"
+    let (row_offset, temporal, column) = ROWS.iter()
+        .flat_map(|(row, offset, temporal_row)| [(*row, *offset, false), (*temporal_row, *offset, row != temporal_row)])
+        .filter(|(row, _, _)| label.starts_with(&row.to_lowercase()))
+        .max_by_key(|(row, _, _)| row.len())
+        .map(|(row, offset, temporal)| (offset, temporal, &label[row.len()..]))?;
+    let column = parse_column(column)?;
+
+    let is_edge_row = row_offset.abs() == 8;
+    let is_temporal_column = |eighths: u32| eighths >= 7 && has_temporal_row(row_offset);
+    let right_side = match column {
+        Column::Midline if !temporal => midline_position(row_offset),
+        // Fp1/Fp2 and O1/O2 lie on the equator
+        Column::Lateral{number: 1 | 2, half: false} if is_edge_row => row_position(row_offset, 8),
+        // 10% below the equator, there is no Fp9/Fp10
+        Column::Lateral{number: 9 | 10, half} if row_offset != 8 && !(is_edge_row && half) && temporal == is_temporal_column(8) => {
+            below_equator(row_offset, if half {11.25} else {22.5})
+        },
+        Column::Lateral{number, half} if !is_edge_row && number <= 8 => {
+            // eighths of the arc from the midline to the equator
+            let eighths = (if number % 2 == 1 {number + 1} else {number}) - u32::from(half);
+            if temporal != is_temporal_column(eighths) {return None}
+            row_position(row_offset, eighths)
+        },
+        _ => return None,
+    };
+
+    Some(match column {
+        Column::Lateral{number, ..} if number % 2 == 1 => SphericalPosition{theta: -right_side.theta, phi: -right_side.phi, ..right_side},
+        _ => right_side,
+    })
+}
+
+/// Rows whose temporal columns 7 to 10 have a different name, e.g. C -> T
+fn has_temporal_row(row_offset: i32) -> bool {
+    ROWS.iter().any(|(row, offset, temporal)| *offset == row_offset && row != temporal)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Midline,
+    /// Odd numbers are on the left, "h" marks the position halfway to the next column towards the midline
+    Lateral{number: u32, half: bool},
+}
+
+fn parse_column(column: &str) -> Option<Column> {
+    if column == "z" {return Some(Column::Midline)}
+    let (digits, half) = match column.strip_suffix('h') {
+        Some(digits) => (digits, true),
+        None => (column, false),
+    };
+    let number = digits.parse::<u32>().ok().filter(|number| (1..=10).contains(number) && !digits.starts_with('0'))?;
+    Some(Column::Lateral{number, half})
+}
+
+fn canonical_label(label: &str) -> String {
+    let label = label.trim();
+    ALIASES.iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(label))
+        .map_or(label.to_string(), |(_, name)| name.to_string())
+}
+
+fn midline_position(row_offset: i32) -> SphericalPosition {
+    let angle = f64::from(row_offset) * 11.25;
+    SphericalPosition{radius: 1., theta: angle.abs(), phi: if row_offset == 0 {0.} else {90f64.copysign(angle)}}
+}
+
+/// Right side position at the row offset, `eighths` of the arc from the midline to the equator
+fn row_position(row_offset: i32, eighths: u32) -> SphericalPosition {
+    let midline = midline_position(row_offset).to_cartesian();
+    let equator = SphericalPosition{radius: 1., theta: 90., phi: f64::from(row_offset) * 9.}.to_cartesian();
+    // the row is the circle through both equator points and the midline point, its plane contains the x-axis
+    let (dy, dz) = (equator.y - midline.y, equator.z - midline.z);
+    let norm = dy.hypot(dz);
+    let (ny, nz) = (-dz / norm, dy / norm);
+    let offset = ny * midline.y + nz * midline.z;
+    let center = CartesianPosition{x: 0., y: offset * ny, z: offset * nz};
+
+    let u = sub(midline, center);
+    let w = sub(equator, center);
+    let radius = norm3(u);
+    let arc = (dot(u, w) / (radius * norm3(w))).clamp(-1., 1.).acos();
+    // v is orthogonal to u in the row plane, pointing towards the right equator point
+    let v_raw = sub(w, scale(u, dot(u, w) / (radius * radius)));
+    let v = scale(v_raw, radius / norm3(v_raw));
+
+    let angle = arc * f64::from(eighths) / 8.;
+    let point = CartesianPosition{
+        x: center.x + angle.cos() * u.x + angle.sin() * v.x,
+        y: center.y + angle.cos() * u.y + angle.sin() * v.y,
+        z: center.z + angle.cos() * u.z + angle.sin() * v.z,
+    };
+    SphericalPosition{
+        radius: 1.,
+        theta: point.z.clamp(-1., 1.).acos().to_degrees(),
+        phi: point.y.atan2(point.x).to_degrees(),
+    }
+}
+
+/// Right side position below the equator end of the row
+fn below_equator(row_offset: i32, degrees: f64) -> SphericalPosition {
+    SphericalPosition{radius: 1., theta: 90. + degrees, phi: f64::from(row_offset) * 9.}
+}
+
+fn grid_labels(include_half: bool) -> Vec<String> {
+    let mut labels = vec!["Nz".to_string()];
+    for (row, offset, temporal) in ROWS {
+        if !include_half && offset % 2 != 0 {continue}
+        labels.push(format!("{row}z"));
+        if offset.abs() == 8 {
+            labels.extend([format!("{row}1"), format!("{row}2")]);
+            labels.extend([format!("{temporal}9"), format!("{temporal}10")]);
+            continue
+        }
+        for number in 1..=10u32 {
+            let name = if number >= 7 {temporal} else {row};
+            labels.push(format!("{name}{number}"));
+            if include_half {labels.push(format!("{name}{number}h"))}
+        }
+    }
+    labels.push("Iz".to_string());
+    labels.retain(|label| standard_position(label).is_some());
+    labels
+}
+
+fn sub(a: CartesianPosition, b: CartesianPosition) -> CartesianPosition {
+    CartesianPosition{x: a.x - b.x, y: a.y - b.y, z: a.z - b.z}
+}
+
+fn scale(a: CartesianPosition, factor: f64) -> CartesianPosition {
+    CartesianPosition{x: a.x * factor, y: a.y * factor, z: a.z * factor}
+}
+
+fn dot(a: CartesianPosition, b: CartesianPosition) -> f64 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn norm3(a: CartesianPosition) -> f64 {
+    dot(a, a).sqrt()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_position(label: &str, theta: f64, phi: f64) {
+        let output = standard_position(label).unwrap();
+        assert!((output.theta - theta).abs() < 1e-9 && (output.phi - phi).abs() < 1e-9, "{label}: {output:?}");
+        assert_eq!(output.radius, 1.);
+    }
+
+    #[test]
+    fn test_standard_position() {
+        assert_position("Cz", 0., 0.);
+        assert_position("Fz", 45., 90.);
+        assert_position("Pz", 45., -90.);
+        assert_position("Fpz", 90., 90.);
+        assert_position("Oz", 90., -90.);
+        assert_position("Fp1", -90., -72.);
+        assert_position("Fp2", 90., 72.);
+        assert_position("F8", 90., 36.);
+        assert_position("T7", -90., 0.);
+        assert_position("C4", 45., 0.);
+        assert_position("C1h", -11.25, 0.);
+        assert_position("P7", -90., 36.);
+        assert_position("TP10", 112.5, -18.);
+        assert_position("Iz", 112.5, -90.);
+    }
+
+    #[test]
+    fn test_standard_position_symmetric() {
+        let left = standard_position("F3").unwrap();
+        let right = standard_position("F4").unwrap();
+        assert_eq!((left.theta, left.phi), (-right.theta, -right.phi));
+        assert!(right.theta > 45. && right.theta < 90. && right.phi > 36. && right.phi < 90.);
+    }
+
+    #[test]
+    fn test_standard_position_names() {
+        assert_eq!(standard_position("fcz"), standard_position("FCz"));
+        assert_eq!(standard_position("T3"), standard_position("T7"));
+        assert_eq!(standard_position("FT8"), standard_position("ft8"));
+        assert_eq!(standard_position("FC8"), Option::None);
+        assert_eq!(standard_position("C7"), Option::None);
+        assert_eq!(standard_position("T4h"), Option::None);
+        assert_eq!(standard_position("Fz0"), Option::None);
+        assert_eq!(standard_position("65"), Option::None);
+    }
+
+    #[test]
+    fn test_montage_labels() {
+        assert_eq!(Montage::Standard1020.labels().len(), 21);
+        assert_eq!(Montage::ActiCap32.labels().len(), 33);
+        assert_eq!(Montage::ActiCap64.labels().len(), 65);
+        assert_eq!(Montage::ActiCap32.labels()[23], "Cz");
+        assert_eq!(Montage::ActiCap64.labels()[23], "Cz");
+        assert_eq!(Montage::ActiCap64.labels()[..32], Montage::ActiCap32.labels()[..32]);
+        let labels_1010 = Montage::Standard1010.labels();
+        assert!(labels_1010.contains(&"TP9".to_string()) && labels_1010.contains(&"AF7".to_string()));
+        assert!(!labels_1010.contains(&"C1h".to_string()));
+        assert!(Montage::Standard1005.labels().contains(&"FFC1h".to_string()));
+        assert_eq!(Montage::EasyCap32.labels().len(), 32);
+        assert_eq!(Montage::EasyCap64.labels().len(), 64);
+        assert_eq!(Montage::EasyCap32.labels()[17], "Cz");
+        assert_eq!(Montage::EasyCap64.labels()[..31], Montage::EasyCap32.labels()[..31]);
+        assert_eq!(Montage::EasyCap64.labels()[31], "F1");
+        for montage in [Montage::Standard1020, Montage::Standard1010, Montage::Standard1005, Montage::ActiCap32, Montage::ActiCap64, Montage::EasyCap32, Montage::EasyCap64] {
+            assert!(montage.labels().iter().all(|label| standard_position(label).is_some()), "{montage:?}");
+        }
+    }
+
+    #[test]
+    fn test_montage_position() {
+        assert_eq!(Montage::Standard1020.position("cz"), standard_position("Cz"));
+        assert_eq!(Montage::Standard1020.position("FCz"), Option::None);
+        assert_eq!(Montage::ActiCap64.position("FCZ"), standard_position("FCz"));
+        assert_eq!(Montage::EasyCap64.position("cpz"), standard_position("CPz"));
+        assert_eq!(Montage::EasyCap32.position("AFz"), Option::None);
+    }
+
+    #[test]
+    fn test_match_labels() {
+        let (positions, unmatched) = Montage::Standard1010.match_labels(&["FP1", "Oz", "65", "EOG"]);
+        assert_eq!(positions[0], standard_position("Fp1"));
+        assert_eq!(positions[1], standard_position("Oz"));
+        assert_eq!(positions[2], Option::None);
+        assert_eq!(unmatched, vec!["65", "EOG"]);
+    }
+
+}
@@ -0,0 +1,173 @@
+//!
+//! This module contains the resolution of the `DataFile` and `MarkerFile` entries relative to the header file
+//!
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::bv_reader::bv_error::Error;
+
+/// Options for [resolve_sibling_path]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolveOptions {
+    /// Also accept files whose name only differs in case, e.g. after copying from FAT-formatted drives
+    pub case_insensitive: bool,
+}
+
+/// Returns the path of a file referenced in the header
+///
+/// Tries in order:
+///
+/// - absolute entries as written in the header
+///
+/// - the entry relative to the directory of the header file
+///
+/// - only the file name of the entry in the directory of the header file
+///
+/// Both `/` and `\` are accepted as separators. Returns Error::FileNotFound with all tried paths if none exists.
+pub fn resolve_sibling_path(headerfile: &Path, entry: &str, options: &ResolveOptions) -> Result<PathBuf, Error> {
+    let header_dir = headerfile.parent().unwrap_or(Path::new(""));
+    let candidates = candidate_paths(header_dir, entry);
+
+    for candidate in candidates.iter() {
+        if candidate.is_file() {return Ok(candidate.clone())}
+    }
+    if options.case_insensitive {
+        if let Some(found) = candidates.iter().find_map(|candidate| find_case_insensitive(candidate)) {
+            return Ok(found)
+        }
+    }
+
+    Err(Error::FileNotFound(
+        entry.to_string(),
+        candidates.iter().map(|candidate| candidate.to_string_lossy().to_string()).collect(),
+    ))
+}
+
+fn candidate_paths(header_dir: &Path, entry: &str) -> Vec<PathBuf> {
+    let components: Vec<&str> = entry.split(['/', '\\']).filter(|part| !part.is_empty()).collect();
+    let Some(file_name) = components.last() else {return vec![]};
+
+    let mut candidates = vec![];
+    if is_absolute_entry(entry) {
+        candidates.push(PathBuf::from(entry));
+        if entry.contains('\\') && !cfg!(windows) {
+            candidates.push(PathBuf::from(entry.replace('\\', "/")));
+        }
+    } else {
+        candidates.push(components.iter().fold(header_dir.to_path_buf(), |path, part| path.join(part)));
+    }
+    let file_in_header_dir = header_dir.join(file_name);
+    if !candidates.contains(&file_in_header_dir) {candidates.push(file_in_header_dir)}
+    candidates
+}
+
+/// Unix absolute paths, Windows drive letters and UNC paths
+fn is_absolute_entry(entry: &str) -> bool {
+    let bytes = entry.as_bytes();
+    entry.starts_with(['/', '\\'])
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+/// Looks for a file in the parent directory of `path` whose name matches case-insensitively
+fn find_case_insensitive(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_str()?.to_lowercase();
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .find(|entry| entry.file_name().to_str().is_some_and(|name| name.to_lowercase() == file_name) && entry.path().is_file())
+        .map(|entry| path.with_file_name(entry.file_name()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bv_reader::header::BVheader;
+
+    const HEADER: &str = "src/bv_reader/data/testfiles/01_header.vhdr";
+
+    #[test]
+    fn test_resolve_sibling_path() {
+        let output = resolve_sibling_path(Path::new(HEADER), "01_data.eeg", &ResolveOptions::default()).unwrap();
+        let expected = PathBuf::from("src/bv_reader/data/testfiles/01_data.eeg");
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_resolve_sibling_path_backslash() {
+        let output = resolve_sibling_path(Path::new(HEADER), "C:\\Recordings\\01_data.eeg", &ResolveOptions::default()).unwrap();
+        let expected = PathBuf::from("src/bv_reader/data/testfiles/01_data.eeg");
+        assert_eq!(output, expected);
+
+        let output = resolve_sibling_path(Path::new("src/bv_reader/data/header.vhdr"), "testfiles\\01_data.eeg", &ResolveOptions::default()).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_resolve_sibling_path_from_header() {
+        let input = "Brain Vision Data Exchange Header File Version 1.0
+        [Common Infos]
+        DataFile=C:\\Recordings\\Subject 01\\01_data.eeg
+        MarkerFile=testfiles\\01_marker.vmrk";
+        let header = BVheader::from_text(input).unwrap();
+        let output = resolve_sibling_path(Path::new(HEADER), &header.data_file, &ResolveOptions::default()).unwrap();
+        let expected = PathBuf::from("src/bv_reader/data/testfiles/01_data.eeg");
+        assert_eq!(output, expected);
+
+        let output = resolve_sibling_path(Path::new("src/bv_reader/data/header.vhdr"), &header.marker_file, &ResolveOptions::default()).unwrap();
+        let expected = PathBuf::from("src/bv_reader/data/testfiles/01_marker.vmrk");
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_resolve_sibling_path_bare_header() {
+        let output = resolve_sibling_path(Path::new("header.vhdr"), "Cargo.toml", &ResolveOptions::default()).unwrap();
+        let expected = PathBuf::from("Cargo.toml");
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_resolve_sibling_path_absolute() {
+        let absolute = fs::canonicalize("src/bv_reader/data/testfiles/01_data.eeg").unwrap();
+        let output = resolve_sibling_path(Path::new("elsewhere/header.vhdr"), absolute.to_str().unwrap(), &ResolveOptions::default()).unwrap();
+        assert_eq!(output, absolute);
+    }
+
+    #[test]
+    fn test_resolve_sibling_path_case_insensitive() {
+        let dir = std::env::temp_dir().join(format!("bvreader_case_insensitive_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Data.eeg"), [0]).unwrap();
+        let header = dir.join("header.vhdr");
+        // on case-insensitive file systems (default on Windows and macOS) the entry is found as written
+        let case_sensitive_fs = !dir.join("DATA.EEG").is_file();
+
+        let output = resolve_sibling_path(&header, "DATA.EEG", &ResolveOptions::default());
+        if case_sensitive_fs {
+            assert!(output.is_err());
+        } else {
+            assert_eq!(output, Ok(dir.join("DATA.EEG")));
+        }
+
+        let output = resolve_sibling_path(&header, "DATA.EEG", &ResolveOptions{case_insensitive: true}).unwrap();
+        let expected = if case_sensitive_fs {dir.join("Data.eeg")} else {dir.join("DATA.EEG")};
+        assert_eq!(output, expected);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_sibling_path_not_found() {
+        let output = resolve_sibling_path(Path::new(HEADER), "sub/missing.eeg", &ResolveOptions::default());
+        let expected = Err(Error::FileNotFound("sub/missing.eeg".to_string(), vec![
+            "src/bv_reader/data/testfiles/sub/missing.eeg".to_string(),
+            "src/bv_reader/data/testfiles/missing.eeg".to_string(),
+        ]));
+        assert_eq!(output, expected);
+    }
+
+}
@@ -2,7 +2,7 @@
 //! This module contains functions for validating the BVFile struct
 //! 
 
-use super::{BVFile, Error};
+use super::{BVFile, Error, Sample};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum IsValid {
@@ -24,7 +24,7 @@ pub fn parse_invalid_to_error(value: &IsValid) -> Error {
 /// Validates that the number of channels is equal across all entries
 /// 
 /// 
-pub fn validate_num_chan(bvfile: &BVFile) -> IsValid {
+pub fn validate_num_chan<T: Sample>(bvfile: &BVFile<T>) -> IsValid {
     let base_err = "Channel mismatch";
     let num_chan = bvfile.bv_header.num_channels;
     if num_chan != bvfile.bv_header.amp_channels {
@@ -2,18 +2,27 @@
 //! 
 //! This crate provides a basic reader for BrainVision Recorder Files `(.vhdr/.vmrk/.eeg)`
 //!
-//! `ONLY FOR HEADER VERSION 1.0`
+//! Supports header versions `1.0` and `2.0`
 //!
 //! Does not include full list of options but is extendable.
 //!
-//! In order to keep it simple all types (FLOAT32, INT16, UINT16) convert to f32.
+//! Binary formats INT_16, UINT_16 and IEEE_FLOAT_32 convert to f32 by default.
+//! INT_32, UINT_32 and IEEE_FLOAT_64 can not be held by f32 without loss of precision and have to be read as f64.
+//! `BVMappedFile` memory-maps the data file and only decodes the requested windows.
+//! `BVNativeFile` keeps the samples in their on-disk type and converts to physical units on request.
 //!
-//! Only implements processing for `Timedomain Data`.
+//! Frequency-domain data stores the frequency resolution in Hz as `sampling_interval`, time-frequency data with `Layers` can be split into one plane per channel (`time_frequency_plane`).
+//! Complex data types (`TIMEDOMAIN_COMPLEX`, `FREQUENCYDOMAIN_COMPLEX`) are read with `BVComplexFile`, which gives magnitude, phase, real and imaginary parts per channel.
+//! Segmented and averaged Analyzer exports are split into `SegmentDataPoints` long segments with `BVFile::segments`.
+//! Electrode positions are read from `[Coordinates]`, headers without them can take approximate positions from a standard montage (`BVheader::apply_montage`).
 //!
 //! See the bv_reader module for usage instructions.
 //!
 //! 
 
+// `is_multiple_of` is only stable since Rust 1.87, remainder checks keep older toolchains working
+#![allow(clippy::manual_is_multiple_of)]
+
 /// ## Usage
 /// ```
 /// 